@@ -0,0 +1,22 @@
+/// Random calorie lists: one block of snack calories per elf, blocks separated by a blank line
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for elf in 0..size * 25 {
+        if elf > 0 {
+            out.push('\n');
+        }
+        for _ in 0..rng.range(1..=15) {
+            writeln!(out, "{}", rng.range(1000..=60_000)).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    assert!(super::top_calories(input.as_bytes())[0] > 0);
+}
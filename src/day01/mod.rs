@@ -1,31 +1,36 @@
-use crate::shared::read_lines;
+use std::io::BufRead;
 
-pub fn run() {
-    println!("=== Day 01 ===");
+use crate::shared::input;
 
-    let input_lines = read_lines("src/day01/input.txt").expect("Couldn't read file");
+pub(crate) mod generate;
 
-    let mut calorie_totals: Vec<usize> = Vec::new();
+/// Sum up each elf's calories as the lines stream past, only keeping the 3 biggest totals
+fn top_calories(reader: impl BufRead) -> [usize; 3] {
+    let mut top = [0; 3];
     let mut total = 0;
-    let mut cur_max = 0;
-
-    for line in input_lines.flatten() {
+    // blank line at the end so the last elf gets counted too
+    for line in input::stream_lines(reader).chain([String::new()]) {
         match line.trim().is_empty() {
             true => {
-                if total > cur_max {
-                    cur_max = total;
+                if total > top[2] {
+                    top[2] = total;
+                    top.sort_by(|a, b| b.cmp(a));
                 }
-                calorie_totals.push(total);
                 total = 0;
-                continue;
             }
             false => {
                 total += line.trim().parse::<usize>().expect("not a number");
             }
         }
     }
-    calorie_totals.sort_by(|a, b| b.cmp(a));
+    top
+}
+
+pub fn run() {
+    println!("=== Day 01 ===");
+
+    let top = top_calories(input::open(1));
 
-    println!("Part 1: {cur_max}");
-    println!("Part 2: {}", calorie_totals[0..3].iter().sum::<usize>());
+    println!("Part 1: {}", top[0]);
+    println!("Part 2: {}", top.iter().sum::<usize>());
 }
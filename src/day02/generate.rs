@@ -0,0 +1,19 @@
+/// Random strategy guides, every line is an opponent move and a response
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size * 250 {
+        let them = rng.choose(&['A', 'B', 'C']);
+        let us = rng.choose(&['X', 'Y', 'Z']);
+        writeln!(out, "{them} {us}").unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::total_scores(input.as_bytes());
+}
@@ -1,4 +1,8 @@
-use crate::shared::read_lines;
+use std::io::BufRead;
+
+use crate::shared::input;
+
+pub(crate) mod generate;
 
 const CHOICE_SCORES: [usize; 3] = [1, 2, 3];
 const WIN_PAIRS: [(char, char); 3] = [('A', 'Y'), ('B', 'Z'), ('C', 'X')];
@@ -28,22 +32,23 @@ fn get_round_score_pt2(round: &(char, char)) -> usize {
     }
 }
 
-pub fn run() {
-    println!("\n=== Day 02 ===");
-    let input = read_lines("src/day02/input.txt")
-        .expect("error reading input file")
-        .map(|l| {
-            let line = l.expect("error reading line");
-            (
+/// Score every round for both parts as it's read
+fn total_scores(reader: impl BufRead) -> (usize, usize) {
+    input::stream_lines(reader)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let round = (
                 line.chars().next().expect("not a char"),
                 line.chars().nth(2).expect("not a char"),
-            )
+            );
+            (get_round_score_pt1(&round), get_round_score_pt2(&round))
         })
-        .collect::<Vec<(char, char)>>();
+        .fold((0, 0), |(p1, p2), (s1, s2)| (p1 + s1, p2 + s2))
+}
 
-    let part1_score: usize = input.iter().map(get_round_score_pt1).sum();
+pub fn run() {
+    println!("\n=== Day 02 ===");
+    let (part1_score, part2_score) = total_scores(input::open(2));
     println!("Part 1: {part1_score}");
-
-    let part2_score: usize = input.iter().map(get_round_score_pt2).sum();
     println!("Part 2: {part2_score}");
 }
@@ -0,0 +1,48 @@
+/// Rucksacks in groups of 3. Each rucksack gets its own pool of item types so the only item the
+/// compartments share is the one picked for it, and the only item all 3 in a group share is the
+/// badge.
+use crate::shared::rng::Rng;
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size * 10 {
+        let mut items = ITEMS.to_vec();
+        rng.shuffle(&mut items);
+        let badge = items.pop().unwrap();
+        // 51 items left, 17 for each rucksack
+        for pool in items.chunks_exact(17) {
+            out.push_str(&rucksack(rng, pool, badge));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn rucksack(rng: &mut Rng, pool: &[u8], badge: u8) -> String {
+    let (shared, rest) = pool.split_first().unwrap();
+    let (left_pool, right_pool) = rest.split_at(rest.len() / 2);
+    let len = rng.range(3..=12);
+    let mut left = vec![*shared];
+    let mut right = vec![*shared];
+    left.extend((1..len).map(|_| *rng.choose(left_pool)));
+    right.extend((1..len).map(|_| *rng.choose(right_pool)));
+    // swap the badge in for one of the unshared items
+    let side = if rng.chance(0.5) {
+        &mut left
+    } else {
+        &mut right
+    };
+    let at = rng.range(1..=len - 1);
+    side[at] = badge;
+    rng.shuffle(&mut left);
+    rng.shuffle(&mut right);
+    left.extend(right);
+    String::from_utf8(left).unwrap()
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::priorities(input.as_bytes());
+}
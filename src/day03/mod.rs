@@ -1,35 +1,31 @@
-use crate::shared::read_lines;
-use std::collections::HashSet;
+use crate::shared::input;
+use std::{collections::HashSet, io::BufRead};
+
+pub(crate) mod generate;
 
 pub fn run() {
     println!("\n=== Day 03 ===");
-    let input = parse_input();
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
-fn part1(input: &Vec<String>) -> usize {
-    let mut total = 0;
-    for rucksack in input {
-        total += get_dup_item_priority_from_rucksack(rucksack) as usize
-    }
-    total
+    let (part1, part2) = priorities(input::open(3));
+    println!("Part 1: {part1}");
+    println!("Part 2: {part2}");
 }
 
-fn part2(input: &[String]) -> usize {
-    let mut total = 0;
-    for group in input.chunks(3) {
-        total += get_shared_item_priority(group) as usize;
+/// Both parts in one pass, only holding on to the current group of 3 rucksacks
+fn priorities(reader: impl BufRead) -> (usize, usize) {
+    let (mut part1, mut part2) = (0, 0);
+    let mut group = Vec::with_capacity(3);
+    let rucksacks = input::stream_lines(reader)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty());
+    for rucksack in rucksacks {
+        part1 += get_dup_item_priority_from_rucksack(&rucksack) as usize;
+        group.push(rucksack);
+        if group.len() == 3 {
+            part2 += get_shared_item_priority(&group) as usize;
+            group.clear();
+        }
     }
-    total
-}
-
-fn parse_input() -> Vec<String> {
-    read_lines("src/day03/input.txt")
-        .expect("Error reading input file")
-        .map(|line| line.expect("error reading line").trim().to_string())
-        .filter(|line| !line.is_empty())
-        .collect()
+    (part1, part2)
 }
 
 fn get_shared_item_priority(rucksacks: &[String]) -> u8 {
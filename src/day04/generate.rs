@@ -0,0 +1,20 @@
+/// Random pairs of section assignments
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size * 100 {
+        let [a, b, c, d] = [(); 4].map(|_| rng.range(1..=99_u32));
+        let (a, b) = (a.min(b), a.max(b));
+        let (c, d) = (c.min(d), c.max(d));
+        writeln!(out, "{a}-{b},{c}-{d}").unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::count_pairs(input.as_bytes());
+}
@@ -1,9 +1,13 @@
-use crate::shared::read_lines;
+use std::io::BufRead;
+
+use crate::shared::{input, IntervalSet};
+
+pub(crate) mod generate;
 
 #[derive(Debug)]
 struct SectionAssignmentPair {
-    p1: (usize, usize),
-    p2: (usize, usize),
+    p1: IntervalSet<usize>,
+    p2: IntervalSet<usize>,
 }
 
 impl SectionAssignmentPair {
@@ -14,50 +18,38 @@ impl SectionAssignmentPair {
             .collect::<Vec<usize>>();
 
         SectionAssignmentPair {
-            p1: (values[0], values[1]),
-            p2: (values[2], values[3]),
+            p1: (values[0], values[1]).into(),
+            p2: (values[2], values[3]).into(),
         }
     }
 
     fn has_fully_contained_assignment(&self) -> bool {
-        ((self.p1.0 <= self.p2.0) && (self.p1.1 >= self.p2.1))
-            || ((self.p2.0 <= self.p1.0) && (self.p2.1 >= self.p1.1))
+        self.p1.is_superset(&self.p2) || self.p2.is_superset(&self.p1)
     }
 
     fn overlaps(&self) -> bool {
-        ((self.p1.0 >= self.p2.0) && (self.p1.0 <= self.p2.1))
-            || ((self.p2.0 >= self.p1.0) && (self.p2.0 <= self.p1.1))
+        !self.p1.is_disjoint(&self.p2)
     }
 }
 
-fn part1(input: &Vec<String>) {
-    let mut total = 0;
-    for line in input {
-        let pairs = SectionAssignmentPair::new(line);
+/// Count (fully contained, overlapping) pairs as they're read
+fn count_pairs(reader: impl BufRead) -> (usize, usize) {
+    let mut totals = (0, 0);
+    for line in input::stream_lines(reader).filter(|l| !l.trim().is_empty()) {
+        let pairs = SectionAssignmentPair::new(line.trim());
         if pairs.has_fully_contained_assignment() {
-            total += 1;
+            totals.0 += 1;
         }
-    }
-    println!("Part 1: {total}");
-}
-
-fn part2(input: &Vec<String>) {
-    let mut total = 0;
-    for line in input {
-        let pairs = SectionAssignmentPair::new(line);
         if pairs.overlaps() {
-            total += 1;
+            totals.1 += 1;
         }
     }
-    println!("Part 2: {total}");
+    totals
 }
 
 pub fn run() {
     println!("\n=== Day 04 ===");
-    let input: Vec<String> = read_lines("src/day04/input.txt")
-        .expect("error reading file")
-        .map(|line| line.expect("error reading line"))
-        .collect();
-    part1(&input);
-    part2(&input);
+    let (part1, part2) = count_pairs(input::open(4));
+    println!("Part 1: {part1}");
+    println!("Part 2: {part2}");
 }
@@ -0,0 +1,63 @@
+/// Random crate stacks and moves. The parser only handles 9 stacks of up to 8 crates with the
+/// first stack the tallest, and no move ever empties a stack, so `get_top_crates` always has a
+/// crate to read.
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+use super::{NUM_STACKS, START_CRATES};
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut stacks = (0..NUM_STACKS)
+        .map(|_| {
+            (0..rng.range(1..=START_CRATES))
+                .map(|_| rng.range(b'A'..=b'Z'))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    // blank crates in the first column aren't recognized, so it has to be the tallest
+    let tallest = (0..NUM_STACKS).max_by_key(|&i| stacks[i].len()).unwrap();
+    stacks.swap(0, tallest);
+
+    let mut out = String::new();
+    for row in (0..stacks[0].len()).rev() {
+        let cells = stacks
+            .iter()
+            .map(|stack| match stack.get(row) {
+                Some(&c) => format!("[{}]", c as char),
+                None => "   ".to_string(),
+            })
+            .collect::<Vec<_>>();
+        writeln!(out, "{}", cells.join(" ")).unwrap();
+    }
+    let numbers = (1..=NUM_STACKS)
+        .map(|n| format!(" {n} "))
+        .collect::<Vec<_>>();
+    writeln!(out, "{}\n", numbers.join(" ")).unwrap();
+
+    let mut heights = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+    for _ in 0..size * 50 {
+        let from = loop {
+            let from = rng.index(NUM_STACKS);
+            if heights[from] > 1 {
+                break from;
+            }
+        };
+        let to = (from + rng.range(1..=NUM_STACKS - 1)) % NUM_STACKS;
+        let amt = rng.range(1..=heights[from] - 1);
+        heights[from] -= amt;
+        heights[to] += amt;
+        writeln!(out, "move {amt} from {} to {}", from + 1, to + 1).unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    use super::{get_moves, populate_stacks, split_input, MAX_CRATES, NUM_STACKS};
+
+    let (diagram, moves) = split_input(input);
+    populate_stacks(&mut [[0; MAX_CRATES]; NUM_STACKS], diagram);
+    // lines that don't read as a move get skipped, so count them
+    assert_eq!(get_moves(moves).len(), moves.lines().count());
+}
@@ -1,4 +1,6 @@
-use crate::shared::read_lines;
+use crate::shared::input;
+
+pub(crate) mod generate;
 
 const NUM_STACKS: usize = 9;
 const START_CRATES: usize = 8;
@@ -7,16 +9,24 @@ const MAX_CRATES: usize = NUM_STACKS * START_CRATES;
 type Stacks = [[u8; MAX_CRATES]; NUM_STACKS];
 type Move = (usize, usize, usize);
 
-/// Take the first 8 lines of the input, and read them as bytes
-/// to be inserted into arrays
+/// Split the input into the crate diagram and the list of moves
+fn split_input(input: &str) -> (&str, &str) {
+    input
+        .split_once("\n\n")
+        .expect("expected the crate diagram and the moves separated by a blank line")
+}
+
+/// Take the crate rows of the diagram (everything above the stack numbers),
+/// and read them as bytes to be inserted into arrays
 #[allow(clippy::needless_range_loop)]
-fn populate_stacks(stacks: &mut Stacks) {
-    let lines = read_lines("src/day05/input.txt")
-        .expect("error reading input file")
-        .map(|l| l.expect("error reading line"));
+fn populate_stacks(stacks: &mut Stacks, diagram: &str) {
+    let mut rows = diagram.lines().collect::<Vec<_>>();
+    // last line just numbers the stacks
+    rows.pop();
+    assert!(rows.len() <= START_CRATES, "too many crates stacked up");
 
-    let mut cur_top_pos = 8;
-    for line in lines.take(8) {
+    let mut cur_top_pos = rows.len();
+    for line in rows {
         cur_top_pos -= 1;
         let mut iter = line.bytes().skip(1);
         stacks[0][cur_top_pos] = iter.next().unwrap();
@@ -33,23 +43,19 @@ fn populate_stacks(stacks: &mut Stacks) {
 
 /// For the rest of the input, output a vector of tuples containing
 /// (number of crates to move, stack to move from, stack to move to)
-fn get_moves() -> Vec<Move> {
+fn get_moves(input: &str) -> Vec<Move> {
     let mut moves = vec![];
-    read_lines("src/day05/input.txt")
-        .expect("error reading input file")
-        .map(|l| l.expect("error reading line"))
-        .skip(10)
-        .for_each(|line| {
-            let values = line
-                .split(' ')
-                .skip(1)
-                .step_by(2)
-                .map(|c| c.parse::<usize>().unwrap())
-                .collect::<Vec<usize>>();
-            if let [amt, from, to] = values[..] {
-                moves.push((amt, from - 1, to - 1));
-            }
-        });
+    input.lines().for_each(|line| {
+        let values = line
+            .split(' ')
+            .skip(1)
+            .step_by(2)
+            .map(|c| c.parse::<usize>().unwrap())
+            .collect::<Vec<usize>>();
+        if let [amt, from, to] = values[..] {
+            moves.push((amt, from - 1, to - 1));
+        }
+    });
     moves
 }
 
@@ -91,10 +97,11 @@ fn get_top_crates(stacks: &Stacks) -> String {
         .collect::<String>()
 }
 
-pub fn part1() {
+pub fn part1(input: &str) {
+    let (diagram, moves) = split_input(input);
     let mut stacks = [[0; MAX_CRATES]; NUM_STACKS];
-    populate_stacks(&mut stacks);
-    let moves = get_moves();
+    populate_stacks(&mut stacks, diagram);
+    let moves = get_moves(moves);
     for move_cmd in moves {
         move_crates(&mut stacks, move_cmd, 1);
     }
@@ -102,10 +109,11 @@ pub fn part1() {
     println!("Part 1: {}", get_top_crates(&stacks));
 }
 
-pub fn part2() {
+pub fn part2(input: &str) {
+    let (diagram, moves) = split_input(input);
     let mut stacks = [[0; MAX_CRATES]; NUM_STACKS];
-    populate_stacks(&mut stacks);
-    let moves = get_moves();
+    populate_stacks(&mut stacks, diagram);
+    let moves = get_moves(moves);
     for move_cmd in moves {
         move_crates(&mut stacks, move_cmd, 2);
     }
@@ -115,6 +123,7 @@ pub fn part2() {
 
 pub fn run() {
     println!("\n=== Day 05 ===");
-    part1();
-    part2();
+    let input = input::load(5);
+    part1(&input);
+    part2(&input);
 }
@@ -0,0 +1,24 @@
+/// A random signal. The start is made from only 13 letters so the 14 letter marker can't show up
+/// until the block of 14 different letters that follows it.
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut letters = (b'a'..=b'z').collect::<Vec<_>>();
+    rng.shuffle(&mut letters);
+    let len = size * 400;
+    let mut signal = (0..len / 4)
+        .map(|_| *rng.choose(&letters[..13]))
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut letters);
+    signal.extend_from_slice(&letters[..14]);
+    let tail = len.saturating_sub(signal.len()).max(1);
+    signal.extend((0..tail).map(|_| rng.range(b'a'..=b'z')));
+    signal.push(b'\n');
+    String::from_utf8(signal).unwrap()
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    let markers = super::get_markers(input.as_bytes(), [4, 14]);
+    assert!(markers.iter().all(Option::is_some));
+}
@@ -1,23 +1,44 @@
-use std::fs::read_to_string;
+use std::{collections::VecDeque, io::BufRead};
+
+use crate::shared::input;
+
+pub(crate) mod generate;
 
 pub fn run() {
     println!("\n=== Day 06 ===");
-    let input = read_to_string("src/day06/input.txt").expect("failed to open file");
-    println!("Part 1: {}", get_marker(&input, 4));
-    println!("Part 2: {}", get_marker(&input, 14));
+    let [part1, part2] = get_markers(input::open(6), [4, 14]);
+    println!("Part 1: {}", part1.expect("no start-of-packet marker"));
+    println!("Part 2: {}", part2.expect("no start-of-message marker"));
 }
 
-fn get_marker(input: &str, len: usize) -> usize {
-    let mut cur_byte = len;
-    let windows = input.as_bytes().windows(len);
+/// Stream the signal once, keeping only the last few bytes around. For each window length,
+/// returns how many bytes have been read once the last `len` bytes are all different.
+fn get_markers<const N: usize>(reader: impl BufRead, lens: [usize; N]) -> [Option<usize>; N] {
+    let max_len = lens.iter().copied().max().unwrap_or(0);
+    let mut window: VecDeque<u8> = VecDeque::with_capacity(max_len + 1);
+    let mut markers = [None; N];
+    // skips line breaks (and a BOM) so the count is in signal characters
+    let signal = reader
+        .bytes()
+        .map(|b| b.expect("error reading input"))
+        .filter(u8::is_ascii_graphic);
 
-    for w in windows {
-        if !has_dup(w) {
-            return cur_byte;
+    for (cur_byte, b) in signal.enumerate() {
+        if window.len() == max_len {
+            window.pop_front();
+        }
+        window.push_back(b);
+        let recent = window.make_contiguous();
+        for (marker, &len) in markers.iter_mut().zip(lens.iter()) {
+            if marker.is_none() && recent.len() >= len && !has_dup(&recent[recent.len() - len..]) {
+                *marker = Some(cur_byte + 1);
+            }
+        }
+        if markers.iter().all(Option::is_some) {
+            break;
         }
-        cur_byte += 1;
     }
-    unreachable!()
+    markers
 }
 
 fn has_dup(slice: &[u8]) -> bool {
@@ -28,3 +49,40 @@ fn has_dup(slice: &[u8]) -> bool {
     }
     false
 }
+
+/// The obvious way: first window whose bytes all go into a `HashSet` without a repeat
+#[cfg(test)]
+fn naive_marker(signal: &[u8], len: usize) -> Option<usize> {
+    signal
+        .windows(len)
+        .position(|w| w.iter().collect::<std::collections::HashSet<_>>().len() == len)
+        .map(|pos| pos + len)
+}
+
+#[test]
+fn test_markers_match_hashset() {
+    use crate::shared::prop::{agree, check, shrink_vec};
+
+    check(
+        "day06 markers",
+        // few letters, so markers are rare and show up anywhere (or nowhere)
+        |rng| {
+            let letters = rng.range(2..=16);
+            let signal = (0..rng.range(0..=120))
+                .map(|_| b'a' + rng.range(0..=letters - 1))
+                .collect::<Vec<_>>();
+            (signal, rng.range(1..=14_usize))
+        },
+        |(signal, len)| {
+            shrink_vec(signal, |_| vec![])
+                .into_iter()
+                .map(|s| (s, *len))
+                .collect()
+        },
+        |(signal, len)| {
+            let fast = get_markers(signal.as_slice(), [*len, 4, 14]);
+            let naive = [*len, 4, 14].map(|l| naive_marker(signal, l));
+            agree(fast, naive)
+        },
+    );
+}
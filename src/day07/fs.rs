@@ -0,0 +1,415 @@
+/// The filesystem a terminal session was looking at, rebuilt from its commands and their output.
+///
+/// `Shell` replays the session: `cd` takes absolute or relative paths (with `..` and `.`) and
+/// keeps the working directory as the stack of directories from the root down, and every `ls`
+/// adds its entries to the working directory. A session can `cd` into a directory no `ls` has
+/// shown yet, which then gets created. Listing a directory again is fine as long as it agrees
+/// with what was seen before; anything that contradicts it is an error.
+use std::collections::BTreeMap;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use custom_error::custom_error;
+
+use super::parse::{Command, Entry, Line};
+
+custom_error! {pub FsError
+    NotFound{path: Utf8PathBuf} = "{path}: no such file or directory",
+    NotADirectory{path: Utf8PathBuf} = "{path}: not a directory",
+    Conflict{path: Utf8PathBuf, was: String, now: String} = "{path} was listed as {was}, now as {now}",
+    NotListing = "directory entry outside of any ls output",
+    AtLine{line: usize, error: Box<FsError>} = "line {line}: {error}"
+}
+
+pub type InodeId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    Dir { children: BTreeMap<String, InodeId> },
+    File { size: u64 },
+}
+
+impl Kind {
+    fn describe(&self) -> String {
+        match self {
+            Kind::Dir { .. } => "a directory".to_string(),
+            Kind::File { size } => format!("a file of {size} bytes"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Inode {
+    pub name: String,
+    /// the root is its own parent
+    pub parent: InodeId,
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone)]
+pub struct Filesystem {
+    inodes: Vec<Inode>,
+}
+
+impl Default for Filesystem {
+    fn default() -> Self {
+        Self {
+            inodes: vec![Inode {
+                name: "/".to_string(),
+                parent: Self::ROOT,
+                kind: Kind::Dir {
+                    children: BTreeMap::new(),
+                },
+            }],
+        }
+    }
+}
+
+impl Filesystem {
+    pub const ROOT: InodeId = 0;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn path(&self, id: InodeId) -> Utf8PathBuf {
+        let mut names = vec![];
+        let mut cur = id;
+        while cur != Self::ROOT {
+            names.push(self.inodes[cur].name.as_str());
+            cur = self.inodes[cur].parent;
+        }
+        let mut path = Utf8PathBuf::from("/");
+        path.extend(names.iter().rev());
+        path
+    }
+
+    pub fn get(&self, id: InodeId) -> &Inode {
+        &self.inodes[id]
+    }
+
+    /// A directory's entries by name; nothing for a file
+    pub fn children(&self, id: InodeId) -> impl Iterator<Item = InodeId> + '_ {
+        let children = match &self.inodes[id].kind {
+            Kind::Dir { children } => Some(children.values().copied()),
+            Kind::File { .. } => None,
+        };
+        children.into_iter().flatten()
+    }
+
+    pub fn is_dir(&self, id: InodeId) -> bool {
+        matches!(self.inodes[id].kind, Kind::Dir { .. })
+    }
+
+    /// Every directory, the root first
+    pub fn dirs(&self) -> impl Iterator<Item = InodeId> + '_ {
+        (0..self.inodes.len()).filter(|&id| self.is_dir(id))
+    }
+
+    /// Size of a file, or of everything under a directory
+    pub fn total_size(&self, id: InodeId) -> u64 {
+        match &self.inodes[id].kind {
+            Kind::File { size } => *size,
+            Kind::Dir { children } => children.values().map(|&c| self.total_size(c)).sum(),
+        }
+    }
+
+    /// The size of every inode at once. Entries are only ever added to directories that are
+    /// already there, so children come after their parents and one backward pass will do.
+    pub fn sizes(&self) -> Vec<u64> {
+        let mut sizes = self
+            .inodes
+            .iter()
+            .map(|inode| match inode.kind {
+                Kind::File { size } => size,
+                Kind::Dir { .. } => 0,
+            })
+            .collect::<Vec<_>>();
+        for id in (1..self.inodes.len()).rev() {
+            sizes[self.inodes[id].parent] += sizes[id];
+        }
+        sizes
+    }
+
+    /// The file or directory at `path`, relative paths starting from the root
+    pub fn lookup(&self, path: &Utf8Path) -> Result<InodeId, FsError> {
+        let mut stack = vec![Self::ROOT];
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            self.resolve(&mut stack, path)?;
+            return Ok(*stack.last().unwrap());
+        };
+        self.resolve(&mut stack, dir)?;
+        let dir = *stack.last().unwrap();
+        self.children(dir)
+            .find(|&c| self.inodes[c].name == name)
+            .ok_or_else(|| FsError::NotFound {
+                path: self.path(dir).join(name),
+            })
+    }
+
+    /// Walk `path` from the directories in `stack` (the root first), leaving the stack at the
+    /// directory it names. Only directories can be walked through or into.
+    pub fn resolve(&self, stack: &mut Vec<InodeId>, path: &Utf8Path) -> Result<(), FsError> {
+        if path.is_absolute() {
+            stack.truncate(1);
+        }
+        for component in path.iter() {
+            match component {
+                "/" | "." => {}
+                ".." => {
+                    if stack.len() > 1 {
+                        stack.pop();
+                    }
+                }
+                name => {
+                    let cur = *stack.last().unwrap();
+                    let child = match &self.inodes[cur].kind {
+                        Kind::Dir { children } => children.get(name).copied(),
+                        Kind::File { .. } => None,
+                    };
+                    let child_path = || self.path(cur).join(name);
+                    match child {
+                        Some(child) if self.is_dir(child) => stack.push(child),
+                        Some(_) => return Err(FsError::NotADirectory { path: child_path() }),
+                        None => return Err(FsError::NotFound { path: child_path() }),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Add `name` to a directory, unless it's already there as the same kind of thing
+    fn add(&mut self, dir: InodeId, name: &str, kind: Kind) -> Result<InodeId, FsError> {
+        let Kind::Dir { children } = &self.inodes[dir].kind else {
+            unreachable!("only directories get listed");
+        };
+        if let Some(&existing) = children.get(name) {
+            let was = &self.inodes[existing].kind;
+            return match (was, &kind) {
+                (Kind::Dir { .. }, Kind::Dir { .. }) => Ok(existing),
+                (was, now) if was == now => Ok(existing),
+                (was, now) => Err(FsError::Conflict {
+                    path: self.path(existing),
+                    was: was.describe(),
+                    now: now.describe(),
+                }),
+            };
+        }
+        let id = self.inodes.len();
+        self.inodes.push(Inode {
+            name: name.to_string(),
+            parent: dir,
+            kind,
+        });
+        if let Kind::Dir { children } = &mut self.inodes[dir].kind {
+            children.insert(name.to_string(), id);
+        }
+        Ok(id)
+    }
+
+    /// Forget every inode from `len` on, undoing the `add`s that made them
+    fn truncate(&mut self, len: usize) {
+        for inode in self.inodes.split_off(len) {
+            // parents that are going too don't need unlinking
+            if let Some(Inode {
+                kind: Kind::Dir { children },
+                ..
+            }) = self.inodes.get_mut(inode.parent)
+            {
+                children.remove(&inode.name);
+            }
+        }
+    }
+}
+
+pub struct Shell {
+    pub fs: Filesystem,
+    /// from the root down to the working directory
+    cwd: Vec<InodeId>,
+    /// whether entries are `ls` output
+    listing: bool,
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        Self {
+            fs: Filesystem::new(),
+            cwd: vec![Filesystem::ROOT],
+            listing: false,
+        }
+    }
+}
+
+impl Shell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cwd(&self) -> InodeId {
+        *self.cwd.last().unwrap()
+    }
+
+    /// Creates whatever directories on the way aren't there yet, but only if the whole path
+    /// works out: otherwise nothing changes, not even the directories it got as far as making
+    pub fn cd(&mut self, path: &Utf8Path) -> Result<(), FsError> {
+        let created = self.fs.inodes.len();
+        match self.walk(path) {
+            Ok(cwd) => {
+                self.cwd = cwd;
+                Ok(())
+            }
+            Err(e) => {
+                self.fs.truncate(created);
+                Err(e)
+            }
+        }
+    }
+
+    /// The working directory `path` leads to, making directories as it goes
+    fn walk(&mut self, path: &Utf8Path) -> Result<Vec<InodeId>, FsError> {
+        let mut cwd = self.cwd.clone();
+        if path.is_absolute() {
+            cwd.truncate(1);
+        }
+        for component in path.iter() {
+            match self.fs.resolve(&mut cwd, Utf8Path::new(component)) {
+                Err(FsError::NotFound { .. }) => {
+                    let dir = *cwd.last().unwrap();
+                    let children = BTreeMap::new();
+                    cwd.push(self.fs.add(dir, component, Kind::Dir { children })?);
+                }
+                result => result?,
+            }
+        }
+        Ok(cwd)
+    }
+
+    pub fn run(&mut self, line: &Line) -> Result<(), FsError> {
+        match line {
+            Line::Command(cmd) => {
+                self.listing = matches!(cmd, Command::Ls);
+                match cmd {
+                    Command::Ls => Ok(()),
+                    Command::Cd(path) => self.cd(path),
+                }
+            }
+            Line::Entry(_) if !self.listing => Err(FsError::NotListing),
+            Line::Entry(entry) => {
+                let (name, kind) = match entry {
+                    Entry::Dir(name) => (
+                        name,
+                        Kind::Dir {
+                            children: BTreeMap::new(),
+                        },
+                    ),
+                    Entry::File(size, name) => (name, Kind::File { size: *size }),
+                };
+                self.fs.add(self.cwd(), name.as_str(), kind).map(|_| ())
+            }
+        }
+    }
+
+    /// Replay a whole session
+    pub fn replay(lines: &[Line]) -> Result<Filesystem, FsError> {
+        let mut shell = Self::new();
+        for (n, line) in lines.iter().enumerate() {
+            shell.run(line).map_err(|e| FsError::AtLine {
+                line: n + 1,
+                error: Box::new(e),
+            })?;
+        }
+        Ok(shell.fs)
+    }
+}
+
+#[cfg(test)]
+pub(super) fn session(text: &str) -> Result<Filesystem, FsError> {
+    Shell::replay(&super::parse::parse_input(text).unwrap())
+}
+
+/// The directory at an absolute path
+#[cfg(test)]
+fn dir_at(fs: &Filesystem, path: &str) -> Result<InodeId, FsError> {
+    let mut stack = vec![Filesystem::ROOT];
+    fs.resolve(&mut stack, path.into())?;
+    Ok(*stack.last().unwrap())
+}
+
+#[test]
+fn test_paths_and_relistings() {
+    let fs = session(
+        "$ cd /\n$ ls\ndir a\ndir empty\n10 x.txt\n$ cd a\n$ ls\ndir b\n20 y\n\
+         $ cd /a/b\n$ ls\n30 z\n$ cd ../../a/./b/..\n$ ls\ndir b\n20 y\n$ cd /\n$ ls\ndir a\n",
+    )
+    .unwrap();
+    let b = dir_at(&fs, "/a/b").unwrap();
+    assert_eq!(fs.path(b), "/a/b");
+    assert_eq!(fs.total_size(b), 30);
+    assert_eq!(fs.total_size(Filesystem::ROOT), 60);
+    // listed, never entered, still there
+    let empty = dir_at(&fs, "/empty").unwrap();
+    assert_eq!(fs.total_size(empty), 0);
+    assert_eq!(fs.dirs().count(), 4);
+    assert!(matches!(
+        dir_at(&fs, "/a/y"),
+        Err(FsError::NotADirectory { .. })
+    ));
+}
+
+#[test]
+fn test_session_errors() {
+    let err = |text| session(text).unwrap_err().to_string();
+    assert_eq!(
+        err("$ ls\n10 x\n$ ls\n11 x\n"),
+        "line 4: /x was listed as a file of 10 bytes, now as a file of 11 bytes"
+    );
+    assert_eq!(
+        err("$ ls\ndir x\n$ ls\n5 x\n"),
+        "line 4: /x was listed as a directory, now as a file of 5 bytes"
+    );
+    assert_eq!(err("$ ls\n5 x\n$ cd x/y\n"), "line 3: /x: not a directory");
+    assert_eq!(
+        err("$ ls\n$ cd /\n5 x\n"),
+        "line 3: directory entry outside of any ls output"
+    );
+}
+
+#[test]
+fn test_cd_into_unlisted_directories() {
+    let fs = session("$ cd /a/b\n$ ls\n30 z\n$ cd ../c\n$ cd /\n$ ls\ndir a\n10 x\n").unwrap();
+    assert_eq!(fs.path(dir_at(&fs, "/a/c").unwrap()), "/a/c");
+    assert_eq!(fs.total_size(dir_at(&fs, "/a").unwrap()), 30);
+    assert_eq!(fs.total_size(Filesystem::ROOT), 40);
+    assert_eq!(fs.dirs().count(), 4);
+    // queries still only find what's there
+    assert!(matches!(
+        fs.lookup("/a/d".into()),
+        Err(FsError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn test_failed_cd_changes_nothing() {
+    let mut shell = Shell::new();
+    for line in super::parse::parse_input("$ ls\n10 x.txt\n$ cd a\n").unwrap() {
+        shell.run(&line).unwrap();
+    }
+    assert!(matches!(
+        shell.cd("new/deeper/../../../x.txt".into()),
+        Err(FsError::NotADirectory { .. })
+    ));
+    assert!(matches!(
+        shell.cd("/b/../x.txt/c".into()),
+        Err(FsError::NotADirectory { .. })
+    ));
+    assert_eq!(shell.fs.path(shell.cwd()), "/a");
+    assert_eq!(shell.fs.dirs().count(), 2);
+    assert!(matches!(
+        shell.fs.lookup("/a/new".into()),
+        Err(FsError::NotFound { .. })
+    ));
+    // and whatever comes next still lines up
+    shell.cd("/b".into()).unwrap();
+    assert_eq!(shell.fs.path(shell.cwd()), "/b");
+    assert_eq!(shell.fs.sizes().len(), 4);
+}
@@ -0,0 +1,118 @@
+/// A random directory tree, explored depth first with `cd` and `ls` like the puzzle's terminal
+/// output. File sizes are scaled so the disk is between 40M and 69M full: part 2 needs something
+/// to delete, but everything still has to fit on the 70M disk.
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+const DISK_MIN: u64 = 40_000_001;
+const DISK_MAX: u64 = 69_000_000;
+
+struct Dir {
+    name: String,
+    files: Vec<(String, u64)>,
+    dirs: Vec<Dir>,
+}
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut root = Dir {
+        name: "/".to_string(),
+        files: vec![],
+        dirs: vec![],
+    };
+    let mut dirs_left = size * 18;
+    grow(rng, &mut root, &mut dirs_left, 0);
+
+    // weights first, then scale them up to the total we're after
+    let total = rng.range(DISK_MIN..=DISK_MAX);
+    let weight = sum_sizes(&root);
+    scale(&mut root, total, weight);
+    // rounding down can leave us a little short of the 40M we need
+    let short = DISK_MIN.saturating_sub(sum_sizes(&root));
+    root.files[0].1 += short;
+
+    let mut out = String::new();
+    write_dir(&root, &mut out, true);
+    out
+}
+
+fn grow(rng: &mut Rng, dir: &mut Dir, dirs_left: &mut usize, depth: usize) {
+    // at least one file each, so the root has one to top up
+    for _ in 0..rng.range(1..=5) {
+        let name = unique_name(rng, dir, true);
+        dir.files.push((name, rng.range(1..=1000)));
+    }
+    let subdirs = match depth {
+        0 => 4,
+        _ if depth > 8 => 0,
+        _ => rng.range(0..=3),
+    };
+    for _ in 0..subdirs {
+        if *dirs_left == 0 {
+            break;
+        }
+        *dirs_left -= 1;
+        let mut sub = Dir {
+            name: unique_name(rng, dir, false),
+            files: vec![],
+            dirs: vec![],
+        };
+        grow(rng, &mut sub, dirs_left, depth + 1);
+        dir.dirs.push(sub);
+    }
+}
+
+fn unique_name(rng: &mut Rng, dir: &Dir, is_file: bool) -> String {
+    loop {
+        let mut name = (0..rng.range(1..=8))
+            .map(|_| rng.range(b'a'..=b'z') as char)
+            .collect::<String>();
+        if is_file && rng.chance(0.6) {
+            name.push('.');
+            name.extend((0..3).map(|_| rng.range(b'a'..=b'z') as char));
+        }
+        let taken =
+            dir.files.iter().any(|(f, _)| *f == name) || dir.dirs.iter().any(|d| d.name == name);
+        if !taken {
+            return name;
+        }
+    }
+}
+
+fn sum_sizes(dir: &Dir) -> u64 {
+    dir.files.iter().map(|(_, s)| s).sum::<u64>() + dir.dirs.iter().map(sum_sizes).sum::<u64>()
+}
+
+fn scale(dir: &mut Dir, total: u64, weight: u64) {
+    for (_, size) in dir.files.iter_mut() {
+        *size = (*size * total / weight).max(1);
+    }
+    for sub in dir.dirs.iter_mut() {
+        scale(sub, total, weight);
+    }
+}
+
+fn write_dir(dir: &Dir, out: &mut String, is_root: bool) {
+    match is_root {
+        true => out.push_str("$ cd /\n"),
+        false => writeln!(out, "$ cd {}", dir.name).unwrap(),
+    }
+    out.push_str("$ ls\n");
+    for sub in dir.dirs.iter() {
+        writeln!(out, "dir {}", sub.name).unwrap();
+    }
+    for (name, size) in dir.files.iter() {
+        writeln!(out, "{size} {name}").unwrap();
+    }
+    for sub in dir.dirs.iter() {
+        write_dir(sub, out, false);
+        out.push_str("$ cd ..\n");
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    use super::{fs::Shell, parse::parse_input};
+
+    Shell::replay(&parse_input(input).unwrap()).unwrap();
+}
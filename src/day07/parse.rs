@@ -1,13 +1,12 @@
-use crate::shared::read_lines;
+use crate::shared::parse::{parse_lines, unsigned, ParseError};
 use camino::Utf8PathBuf;
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_while1},
-    combinator::{all_consuming, map},
+    combinator::map,
     sequence::{preceded, separated_pair},
-    Finish, IResult,
+    IResult,
 };
-use std::io::Error;
 
 #[derive(Debug)]
 pub enum Line {
@@ -46,10 +45,7 @@ struct Ls;
 struct Cd(Utf8PathBuf);
 
 fn parse_path(i: &str) -> IResult<&str, Utf8PathBuf> {
-    map(
-        take_while1(|c: char| "abcdefghijklmnopqrstuvwxyz./".contains(c)),
-        Into::into,
-    )(i)
+    map(take_while1(|c: char| !c.is_whitespace()), Into::into)(i)
 }
 
 fn parse_ls(i: &str) -> IResult<&str, Ls> {
@@ -62,7 +58,7 @@ fn parse_cd(i: &str) -> IResult<&str, Cd> {
 
 fn parse_entry(i: &str) -> IResult<&str, Entry> {
     let parse_file = map(
-        separated_pair(nom::character::complete::u64, tag(" "), parse_path),
+        separated_pair(unsigned, tag(" "), parse_path),
         |(size, path)| Entry::File(size, path),
     );
     let parse_dir = map(preceded(tag("dir "), parse_path), Entry::Dir);
@@ -82,18 +78,6 @@ fn parse_line(i: &str) -> IResult<&str, Line> {
     ))(i)
 }
 
-pub(super) fn parse_input(path: &str) -> Result<Vec<Line>, Error> {
-    let lines = read_lines(path)?;
-    Ok(lines
-        .map(|l| {
-            if let Ok(l_val) = l {
-                all_consuming(parse_line)(l_val.as_str())
-                    .finish()
-                    .unwrap()
-                    .1
-            } else {
-                panic!()
-            }
-        })
-        .collect())
+pub(super) fn parse_input(input: &str) -> Result<Vec<Line>, ParseError> {
+    parse_lines(input, parse_line)
 }
@@ -0,0 +1,305 @@
+/// Looking around a rebuilt filesystem: the tree with sizes, `du` down to a depth, `find` by size
+/// and name, the largest directories, and the whole thing as JSON. Sizes are worked out once up
+/// front, so every query is a single walk.
+use std::fmt::Write;
+
+use camino::Utf8Path;
+use serde_json::{json, Value};
+
+use super::{
+    fs::{Filesystem, InodeId, Kind, Shell},
+    parse::parse_input,
+};
+use crate::shared::input;
+
+/// What `find` is looking for; everything when left empty
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    /// a glob on the name, with `*` and `?`
+    pub name: Option<String>,
+}
+
+impl Filter {
+    fn matches(&self, name: &str, size: u64) -> bool {
+        self.min_size.is_none_or(|min| size >= min)
+            && self.max_size.is_none_or(|max| size <= max)
+            && self.name.as_ref().is_none_or(|pattern| {
+                glob(
+                    &pattern.chars().collect::<Vec<_>>(),
+                    &name.chars().collect::<Vec<_>>(),
+                )
+            })
+    }
+}
+
+fn glob(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, _) => name.is_empty(),
+        (Some('*'), _) => {
+            glob(&pattern[1..], name) || (!name.is_empty() && glob(pattern, &name[1..]))
+        }
+        (Some(_), None) => false,
+        (Some(&p), Some(&c)) => (p == '?' || p == c) && glob(&pattern[1..], &name[1..]),
+    }
+}
+
+pub struct Query<'a> {
+    fs: &'a Filesystem,
+    sizes: Vec<u64>,
+}
+
+impl<'a> Query<'a> {
+    pub fn new(fs: &'a Filesystem) -> Self {
+        Self {
+            fs,
+            sizes: fs.sizes(),
+        }
+    }
+
+    pub fn size(&self, id: InodeId) -> u64 {
+        self.sizes[id]
+    }
+
+    /// Like the puzzle's example, with directory sizes too
+    pub fn tree(&self, from: InodeId) -> String {
+        let mut out = String::new();
+        self.walk(from, 0, &mut |id, depth| {
+            let inode = self.fs.get(id);
+            let kind = if self.fs.is_dir(id) { "dir" } else { "file" };
+            writeln!(
+                out,
+                "{:indent$}- {} ({kind}, size={})",
+                "",
+                inode.name,
+                self.size(id),
+                indent = 2 * depth
+            )
+            .unwrap();
+        });
+        out
+    }
+
+    /// Directories at most `depth` below `from`, each after everything in it, like `du -d`
+    pub fn du(&self, from: InodeId, depth: usize) -> Vec<InodeId> {
+        let mut dirs = vec![];
+        self.du_into(from, depth, &mut dirs);
+        dirs
+    }
+
+    fn du_into(&self, id: InodeId, depth: usize, dirs: &mut Vec<InodeId>) {
+        if depth > 0 {
+            for child in self.fs.children(id).filter(|&c| self.fs.is_dir(c)) {
+                self.du_into(child, depth - 1, dirs);
+            }
+        }
+        dirs.push(id);
+    }
+
+    /// Files and directories under `from` (and `from` itself) that pass the filter
+    pub fn find(&self, from: InodeId, filter: &Filter) -> Vec<InodeId> {
+        let mut found = vec![];
+        self.walk(from, 0, &mut |id, _| {
+            if filter.matches(&self.fs.get(id).name, self.size(id)) {
+                found.push(id);
+            }
+        });
+        found
+    }
+
+    /// The `n` largest directories, biggest first
+    pub fn largest_dirs(&self, n: usize) -> Vec<InodeId> {
+        let mut dirs = self.fs.dirs().collect::<Vec<_>>();
+        dirs.sort_by_key(|&d| (std::cmp::Reverse(self.size(d)), self.fs.path(d)));
+        dirs.truncate(n);
+        dirs
+    }
+
+    pub fn to_json(&self, from: InodeId) -> Value {
+        let inode = self.fs.get(from);
+        match &inode.kind {
+            Kind::File { size } => json!({ "name": inode.name, "type": "file", "size": size }),
+            Kind::Dir { .. } => json!({
+                "name": inode.name,
+                "type": "dir",
+                "size": self.size(from),
+                "children": self.fs.children(from).map(|c| self.to_json(c)).collect::<Vec<_>>(),
+            }),
+        }
+    }
+
+    /// `size<TAB>path` lines, like `du` prints them
+    pub fn listing(&self, ids: &[InodeId]) -> String {
+        let mut out = String::new();
+        for &id in ids {
+            writeln!(out, "{}\t{}", self.size(id), self.fs.path(id)).unwrap();
+        }
+        out
+    }
+
+    /// Depth first, a directory before what's in it
+    fn walk(&self, id: InodeId, depth: usize, visit: &mut impl FnMut(InodeId, usize)) {
+        visit(id, depth);
+        for child in self.fs.children(id) {
+            self.walk(child, depth + 1, visit);
+        }
+    }
+}
+
+/// `fs [--input FILE] <command>`: query the filesystem a session dump (the day's input by
+/// default) was looking at
+pub(crate) fn cli(args: &[String]) {
+    let usage = "usage: fs [--input FILE] tree [PATH] | du [-d N] [PATH] \
+                 | find [PATH] [--min-size N] [--max-size N] [--name GLOB] | top [N] | json [PATH]";
+    let (path, args) = match args {
+        [flag, path, rest @ ..] if flag == "--input" => (Some(path.as_str()), rest),
+        _ => (None, args),
+    };
+    let Some((command, args)) = args.split_first() else {
+        return eprintln!("{usage}");
+    };
+    let text = match path {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(raw) => input::prepare(7, &raw),
+            Err(e) => return eprintln!("can't read {path}: {e}"),
+        },
+        None => input::load(7),
+    };
+    let lines = match parse_input(&text) {
+        Ok(lines) => lines,
+        Err(e) => return eprintln!("{}: {e}", path.unwrap_or("day 07 input")),
+    };
+    let fs = match Shell::replay(&lines) {
+        Ok(fs) => fs,
+        Err(e) => return eprintln!("{e}"),
+    };
+    let query = Query::new(&fs);
+    // `None` once it's said what's wrong with the path
+    let lookup = |path: Option<&String>| {
+        path.map_or(Ok(Filesystem::ROOT), |p| fs.lookup(Utf8Path::new(p)))
+            .map_err(|e| eprintln!("{e}"))
+            .ok()
+    };
+
+    match command.as_str() {
+        "tree" if args.len() <= 1 => {
+            if let Some(id) = lookup(args.first()) {
+                print!("{}", query.tree(id))
+            }
+        }
+        "json" if args.len() <= 1 => {
+            if let Some(id) = lookup(args.first()) {
+                println!("{:#}", query.to_json(id))
+            }
+        }
+        "du" => {
+            let (depth, path) = match args {
+                [flag, depth, path @ ..] if flag == "-d" => (depth.parse().ok(), path),
+                path => (Some(usize::MAX), path),
+            };
+            match (depth, path) {
+                (Some(depth), [] | [_]) => {
+                    if let Some(id) = lookup(path.first()) {
+                        print!("{}", query.listing(&query.du(id, depth)))
+                    }
+                }
+                _ => eprintln!("{usage}"),
+            }
+        }
+        "top" => match args {
+            [] => print!("{}", query.listing(&query.largest_dirs(10))),
+            [n] if n.parse::<usize>().is_ok() => {
+                print!("{}", query.listing(&query.largest_dirs(n.parse().unwrap())))
+            }
+            _ => eprintln!("{usage}"),
+        },
+        "find" => {
+            let (path, mut flags) = match args.split_first() {
+                Some((path, rest)) if !path.starts_with("--") => (Some(path), rest.iter()),
+                _ => (None, args.iter()),
+            };
+            let mut filter = Filter::default();
+            while let Some(flag) = flags.next() {
+                let Some(val) = flags.next() else {
+                    return eprintln!("{usage}");
+                };
+                match (flag.as_str(), val.parse::<u64>().ok()) {
+                    ("--min-size", Some(size)) => filter.min_size = Some(size),
+                    ("--max-size", Some(size)) => filter.max_size = Some(size),
+                    ("--name", _) => filter.name = Some(val.clone()),
+                    _ => return eprintln!("{usage}"),
+                }
+            }
+            if let Some(id) = lookup(path) {
+                print!("{}", query.listing(&query.find(id, &filter)));
+            }
+        }
+        _ => eprintln!("{usage}"),
+    }
+}
+
+#[test]
+fn test_queries() {
+    use super::fs::session;
+
+    let fs = session(include_str!("sample_input.txt")).unwrap();
+    let query = Query::new(&fs);
+    assert_eq!(
+        query.tree(Filesystem::ROOT),
+        "- / (dir, size=48381165)\n  \
+         - a (dir, size=94853)\n    \
+         - e (dir, size=584)\n      \
+         - i (file, size=584)\n    \
+         - f (file, size=29116)\n    \
+         - g (file, size=2557)\n    \
+         - h.lst (file, size=62596)\n  \
+         - b.txt (file, size=14848514)\n  \
+         - c.dat (file, size=8504156)\n  \
+         - d (dir, size=24933642)\n    \
+         - d.ext (file, size=5626152)\n    \
+         - d.log (file, size=8033020)\n    \
+         - j (file, size=4060174)\n    \
+         - k (file, size=7214296)\n"
+    );
+    assert_eq!(
+        query.listing(&query.du(Filesystem::ROOT, 1)),
+        "94853\t/a\n24933642\t/d\n48381165\t/\n"
+    );
+    assert_eq!(query.du(Filesystem::ROOT, 9).len(), 4);
+
+    let a = fs.lookup("a".into()).unwrap();
+    let found = |filter| query.listing(&query.find(Filesystem::ROOT, &filter));
+    assert_eq!(
+        found(Filter {
+            max_size: Some(100_000),
+            ..Filter::default()
+        }),
+        "94853\t/a\n584\t/a/e\n584\t/a/e/i\n29116\t/a/f\n2557\t/a/g\n62596\t/a/h.lst\n"
+    );
+    assert_eq!(
+        found(Filter {
+            min_size: Some(8_000_000),
+            name: Some("*.*".to_string()),
+            ..Filter::default()
+        }),
+        "14848514\t/b.txt\n8504156\t/c.dat\n8033020\t/d/d.log\n"
+    );
+    let one_letter = Filter {
+        name: Some("?".to_string()),
+        ..Filter::default()
+    };
+    assert_eq!(
+        query.listing(&query.find(a, &one_letter)),
+        "94853\t/a\n584\t/a/e\n584\t/a/e/i\n29116\t/a/f\n2557\t/a/g\n"
+    );
+    assert_eq!(
+        query.listing(&query.largest_dirs(2)),
+        "48381165\t/\n24933642\t/d\n"
+    );
+    assert_eq!(
+        query.to_json(fs.lookup("/a/e".into()).unwrap()).to_string(),
+        r#"{"children":[{"name":"i","size":584,"type":"file"}],"name":"e","size":584,"type":"dir"}"#
+    );
+    assert!(fs.lookup("/a/nope".into()).is_err());
+}
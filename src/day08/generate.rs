@@ -0,0 +1,17 @@
+/// A random square forest of tree heights
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let side = (size * 10).max(3);
+    let mut out = String::with_capacity(side * (side + 1));
+    for _ in 0..side {
+        out.extend((0..side).map(|_| rng.range(b'0'..=b'9') as char));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::parse_grid(input);
+}
@@ -1,7 +1,9 @@
 /// Super inefficient way to do Day 8 of AoC 2022 -- but it does work, so yay.
-use crate::shared::{Grid, GridCoord};
+use crate::shared::{input, Grid, GridCoord};
 use std::collections::HashSet;
 
+pub(crate) mod generate;
+
 #[allow(dead_code)]
 const SAMPLE_INPUT: &str = "\
 30373
@@ -146,8 +148,8 @@ fn part2(grid: &Grid<usize>) {
 
 pub fn run() {
     println!("\n=== Day 08 ===");
-    let input_str = include_str!("input.txt");
-    let grid = parse_grid(input_str);
+    let input = input::prepare(8, include_str!("input.txt"));
+    let grid = parse_grid(&input);
 
     let visible = get_total_visible_pt1(&grid);
     println!("Part 1: {}", visible.len());
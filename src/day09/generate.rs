@@ -0,0 +1,18 @@
+/// Random rope motions
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size * 200 {
+        let dir = rng.choose(&['U', 'D', 'L', 'R']);
+        writeln!(out, "{dir} {}", rng.range(1..=20)).unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::parse::parse_input(input).unwrap();
+}
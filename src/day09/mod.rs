@@ -1,3 +1,4 @@
+pub(crate) mod generate;
 mod parse;
 
 use std::collections::{HashSet, VecDeque};
@@ -5,6 +6,7 @@ use std::collections::{HashSet, VecDeque};
 use parse::{parse_input, GridPos};
 
 use self::parse::Instruction;
+use crate::shared::input;
 
 const NUM_KNOTS: usize = 10;
 const TAIL_IDX: usize = NUM_KNOTS - 1;
@@ -17,8 +19,8 @@ struct Simulation {
 }
 
 impl Simulation {
-    fn new(path: &str) -> Self {
-        let instructions = parse_input(path).expect("error reading input file");
+    fn new(input: &str) -> Self {
+        let instructions = parse_input(input).unwrap_or_else(|e| panic!("{e}"));
 
         Self {
             instructions,
@@ -89,7 +91,7 @@ fn get_tail_move(diff: GridPos) -> GridPos {
 
 pub fn run() {
     println!("\n=== Day 09 ===");
-    let mut sim = Simulation::new("src/day09/input.txt");
+    let mut sim = Simulation::new(&input::load(9));
     while !sim.instructions.is_empty() {
         sim.update_state();
     }
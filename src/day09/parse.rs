@@ -1,13 +1,13 @@
-use crate::shared::read_lines;
+use crate::shared::parse::{parse_lines, unsigned, ParseError};
 use std::{collections::VecDeque, fmt};
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::space1,
-    combinator::{all_consuming, map, value},
+    combinator::{map, value},
     sequence::{preceded, tuple},
-    Finish, IResult,
+    IResult,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -97,27 +97,12 @@ pub(crate) struct Instruction {
 impl Instruction {
     pub(crate) fn parse(i: &str) -> IResult<&str, Self> {
         map(
-            tuple((
-                Direction::parse,
-                preceded(space1, nom::character::complete::u32),
-            )),
+            tuple((Direction::parse, preceded(space1, unsigned))),
             |(dir, dist)| Self { dir, dist },
         )(i)
     }
 }
 
-pub(crate) fn parse_input(path: &str) -> Result<VecDeque<Instruction>, std::io::Error> {
-    let lines = read_lines(path)?;
-    Ok(lines
-        .map(|l| {
-            if let Ok(l_val) = l {
-                all_consuming(Instruction::parse)(l_val.as_str())
-                    .finish()
-                    .unwrap()
-                    .1
-            } else {
-                panic!("Couldn't parse instruction from line")
-            }
-        })
-        .collect())
+pub(crate) fn parse_input(input: &str) -> Result<VecDeque<Instruction>, ParseError> {
+    Ok(parse_lines(input, Instruction::parse)?.into())
 }
@@ -0,0 +1,349 @@
+/// Writing programs for the CRT instead of just running them: `assemble` turns a 40x6 picture
+/// (or some text, drawn in the CRT's font) into `addx`/`noop`s that draw it, and `disassemble`
+/// lists a program with the cycle each instruction starts on and what x is at the time.
+///
+/// Every pixel is lit exactly when the sprite covers it, so x has to be in the right place on
+/// every cycle, and it only moves after an `addx`, which holds it for 2 cycles first. A table
+/// built backwards from the last cycle says how few instructions each (cycle, x) state needs to
+/// finish the picture, if it can at all, and the program is then walked forwards through those.
+use std::{fmt::Write, ops::RangeInclusive};
+
+use custom_error::custom_error;
+
+use super::{crt::Crt, parse_input, registers, Instruction};
+use crate::shared::{ocr::FONT_4X6, rng::Rng, vm::Vm};
+
+const WIDTH: usize = 40;
+const HEIGHT: usize = 6;
+const CYCLES: usize = WIDTH * HEIGHT;
+/// Wide enough to park the sprite off either edge of the screen
+const REGISTER: RangeInclusive<i32> = -3..=45;
+
+custom_error! {pub AsmError
+    BadSize{width: usize, height: usize} = "the CRT is 40x6, can't draw a {width}x{height} bitmap",
+    UnknownLetter{letter: char} = "the CRT font has no '{letter}'",
+    TooLong{len: usize} = "only 8 letters fit on the CRT, got {len}",
+    Undrawable = "no program can draw that, x starts at 1 and can't keep up with it",
+    Mismatch{pixel: usize} = "the program draws pixel {pixel} wrong"
+}
+
+/// A picture from lines of `#` (lit) and anything else (dark)
+pub(crate) fn bitmap(screen: &str) -> Result<Vec<bool>, AsmError> {
+    let rows = screen.lines().collect::<Vec<_>>();
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    if rows.len() != HEIGHT || rows.iter().any(|r| r.len() != WIDTH) {
+        return Err(AsmError::BadSize {
+            width,
+            height: rows.len(),
+        });
+    }
+    Ok(rows
+        .iter()
+        .flat_map(|r| r.bytes().map(|b| b == b'#'))
+        .collect())
+}
+
+/// A picture of `text` in the CRT's font. Spaces are blank letters.
+pub(crate) fn text(text: &str) -> Result<Vec<bool>, AsmError> {
+    let pitch = FONT_4X6.width + FONT_4X6.spacing;
+    let len = text.chars().count();
+    if len > WIDTH / pitch {
+        return Err(AsmError::TooLong { len });
+    }
+    let glyphs = text
+        .chars()
+        .map(|letter| match letter {
+            ' ' => Ok(None),
+            _ => FONT_4X6
+                .glyph(letter)
+                .map(Some)
+                .ok_or(AsmError::UnknownLetter { letter }),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((0..CYCLES)
+        .map(|pixel| {
+            let (row, col) = (pixel / WIDTH, pixel % WIDTH);
+            match glyphs.get(col / pitch) {
+                Some(Some(glyph)) => glyph[row].as_bytes().get(col % pitch) == Some(&b'#'),
+                _ => false,
+            }
+        })
+        .collect())
+}
+
+/// The shortest program for the picture: as few instructions as possible, so as many cycles as
+/// possible go to `addx`
+pub(crate) fn assemble(pixels: &[bool]) -> Result<Vec<Instruction>, AsmError> {
+    walk(pixels, |costs| {
+        (0..costs.len()).min_by_key(|&k| costs[k]).unwrap()
+    })
+}
+
+/// Any one of the programs for the picture
+pub(crate) fn assemble_random(
+    rng: &mut Rng,
+    pixels: &[bool],
+) -> Result<Vec<Instruction>, AsmError> {
+    walk(pixels, |costs| rng.index(costs.len()))
+}
+
+/// How a hold of x ends
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    /// an `addx` in its last 2 cycles moves x
+    Move(usize),
+    /// `noop`s until the picture is done
+    Finish(usize),
+}
+
+/// The two cheapest (cost, register index) pairs at some cycle, `usize::MAX` if there aren't
+type Top = [(usize, usize); 2];
+
+/// Cheapest way to finish from any register but `i`
+fn elsewhere(top: &Top, i: usize) -> Option<usize> {
+    let (cost, _) = top[usize::from(top[0].1 == i)];
+    (cost != usize::MAX).then_some(cost)
+}
+
+/// Each way the hold starting at `cycle` with x at register `i` can end, and how many
+/// instructions it takes from there to finish the picture
+fn steps<'a>(
+    holds: &'a [Vec<usize>],
+    top: &'a [Top],
+    cycle: usize,
+    i: usize,
+) -> impl Iterator<Item = (Step, usize)> + 'a {
+    let held = holds[cycle][i];
+    let finish = (cycle + held == CYCLES).then_some((Step::Finish(held), held));
+    (2..=held)
+        .filter_map(move |len| {
+            elsewhere(&top[cycle + len], i).map(|rest| (Step::Move(len), len - 1 + rest))
+        })
+        .chain(finish)
+}
+
+/// Build the program forwards. At each point `pick` gets the instructions every option still
+/// needs to finish the picture, and chooses one of them: first how long to hold x and whether
+/// to move it at the end, then where to.
+fn walk(
+    pixels: &[bool],
+    mut pick: impl FnMut(&[usize]) -> usize,
+) -> Result<Vec<Instruction>, AsmError> {
+    let registers = REGISTER.collect::<Vec<_>>();
+    let lit_right = |rx: i32, cycle: usize| {
+        let sprite = (rx - 1)..=(rx + 1);
+        sprite.contains(&((cycle % WIDTH) as i32)) == pixels[cycle]
+    };
+    // holds[cycle][i]: how many cycles in a row x could stay at registers[i] from here
+    let mut holds = vec![vec![0; registers.len()]; CYCLES + 1];
+    for cycle in (0..CYCLES).rev() {
+        for (i, &rx) in registers.iter().enumerate() {
+            if lit_right(rx, cycle) {
+                holds[cycle][i] = holds[cycle + 1][i] + 1;
+            }
+        }
+    }
+
+    // best[cycle][i]: fewest instructions that finish the picture when x starts holding
+    // registers[i] at this cycle, and top[cycle] the two smallest of those (with their i) so
+    // "anywhere but i" is quick to look up
+    let mut best = vec![vec![None; registers.len()]; CYCLES + 1];
+    let mut top: Vec<Top> = vec![[(usize::MAX, usize::MAX); 2]; CYCLES + 1];
+    best[CYCLES].fill(Some(0));
+    top[CYCLES] = [(0, 0), (0, 1)];
+    for cycle in (0..CYCLES).rev() {
+        for (i, slot) in best[cycle].iter_mut().enumerate() {
+            let cost = steps(&holds, &top, cycle, i).map(|(_, c)| c).min();
+            *slot = cost;
+            if let Some(cost) = cost {
+                if (cost, i) < top[cycle][0] {
+                    top[cycle] = [(cost, i), top[cycle][0]];
+                } else if (cost, i) < top[cycle][1] {
+                    top[cycle][1] = (cost, i);
+                }
+            }
+        }
+    }
+
+    let (mut cycle, mut i) = (0, registers.iter().position(|&rx| rx == 1).unwrap());
+    if best[cycle][i].is_none() {
+        return Err(AsmError::Undrawable);
+    }
+    let mut program = vec![];
+    while cycle < CYCLES {
+        let options = steps(&holds, &top, cycle, i).collect::<Vec<_>>();
+        let costs = options.iter().map(|&(_, c)| c).collect::<Vec<_>>();
+        match options[pick(&costs)].0 {
+            Step::Finish(len) => {
+                program.extend(std::iter::repeat_n(Instruction::Noop, len));
+                break;
+            }
+            Step::Move(len) => {
+                let moves = (0..registers.len())
+                    .filter(|&j| j != i)
+                    .filter_map(|j| best[cycle + len][j].map(|cost| (j, cost)))
+                    .collect::<Vec<_>>();
+                let costs = moves.iter().map(|&(_, c)| c).collect::<Vec<_>>();
+                let next = moves[pick(&costs)].0;
+                program.extend(std::iter::repeat_n(Instruction::Noop, len - 2));
+                program.push(Instruction::Addx(registers[next] - registers[i]));
+                (cycle, i) = (cycle + len, next);
+            }
+        }
+    }
+    Ok(program)
+}
+
+/// Run the program on the CRT and make sure it draws exactly the picture
+pub(crate) fn verify(program: &[Instruction], pixels: &[bool]) -> Result<(), AsmError> {
+    let mut crt = Crt::standard();
+    Vm::new(registers(), program.iter().copied()).run(&mut [&mut crt]);
+    let drawn = bitmap(&crt.screen())?;
+    match (0..CYCLES).find(|&p| drawn[p] != pixels[p]) {
+        Some(p) => Err(AsmError::Mismatch { pixel: p + 1 }),
+        None => Ok(()),
+    }
+}
+
+/// One line per instruction: the cycle it starts on, x at the time, then the instruction
+pub(crate) fn disassemble(program: &[Instruction]) -> String {
+    use crate::shared::vm::Instruction as _;
+
+    let mut registers = registers();
+    let mut listing = String::from("cycle    x  instruction\n");
+    let mut cycle = 1;
+    for inst in program {
+        writeln!(listing, "{cycle:>5} {:>4}  {inst}", registers.get("x")).unwrap();
+        cycle += inst.cycles();
+        inst.execute(&mut registers);
+    }
+    listing
+}
+
+/// Program text, one instruction per line, the way puzzle inputs are written
+pub(crate) fn program_text(program: &[Instruction]) -> String {
+    program.iter().map(|inst| format!("{inst}\n")).collect()
+}
+
+/// `asm <TEXT>` or `asm --bitmap <FILE>`: print a program that draws it, once it's been checked
+/// on the CRT. `disasm <FILE>`: list a program with its cycles and x.
+pub(crate) fn cli(command: &str, args: &[String]) {
+    let usage = "usage: asm <TEXT> | asm --bitmap <FILE> | disasm <FILE>";
+    let read = |path: &String| {
+        std::fs::read_to_string(path)
+            .map_err(|e| eprintln!("can't read {path}: {e}"))
+            .ok()
+    };
+    match (command, args) {
+        ("disasm", [path]) => {
+            if let Some(source) = read(path) {
+                let program = parse_input(source.as_bytes()).collect::<Vec<_>>();
+                print!("{}", disassemble(&program));
+            }
+        }
+        ("asm", [flag, path]) if flag == "--bitmap" => {
+            if let Some(screen) = read(path) {
+                print_program(bitmap(&screen))
+            }
+        }
+        ("asm", [word]) => print_program(text(word)),
+        _ => eprintln!("{usage}"),
+    }
+}
+
+fn print_program(pixels: Result<Vec<bool>, AsmError>) {
+    let program = pixels.and_then(|pixels| {
+        let program = assemble(&pixels)?;
+        verify(&program, &pixels)?;
+        Ok(program)
+    });
+    match program {
+        Ok(program) => print!("{}", program_text(&program)),
+        Err(e @ AsmError::Undrawable) => eprintln!(
+            "{e}\nthe sprite starts out over the first two pixels, so they have to be lit: \
+            the first letter must light both of them"
+        ),
+        Err(e) => eprintln!("{e}"),
+    }
+}
+
+#[test]
+fn test_assemble_text() {
+    let pixels = text("RFZEKBFA").unwrap();
+    let program = assemble(&pixels).unwrap();
+    verify(&program, &pixels).unwrap();
+    // 240 cycles take at least 120 instructions, all of them `addx`
+    assert_eq!(program.len(), 124);
+    let listing = disassemble(&program);
+    assert!(listing.starts_with("cycle    x  instruction\n    1    1  "));
+    assert_eq!(listing.lines().count(), program.len() + 1);
+    // a different picture isn't drawn by the same program
+    let other = text("RFZEKBFB").unwrap();
+    assert!(matches!(
+        verify(&program, &other),
+        Err(AsmError::Mismatch { .. })
+    ));
+
+    assert!(matches!(
+        text("ABCDEFGHI"),
+        Err(AsmError::TooLong { len: 9 })
+    ));
+    assert!(matches!(
+        text("HeLLO"),
+        Err(AsmError::UnknownLetter { letter: 'e' })
+    ));
+    assert!(matches!(
+        bitmap("#\n#\n"),
+        Err(AsmError::BadSize {
+            width: 1,
+            height: 2
+        })
+    ));
+    // x starts at 1, so pixel 1 can't be dark while pixel 3 is lit
+    let mut corner = vec![false; CYCLES];
+    corner[2] = true;
+    assert!(matches!(assemble(&corner), Err(AsmError::Undrawable)));
+}
+
+#[test]
+fn test_round_trip_through_vm() {
+    use crate::shared::{
+        ocr,
+        prop::{agree, check, no_shrink},
+    };
+
+    // x starts at 1, so only letters that light both of the first two pixels can go first
+    const FIRST: [char; 6] = ['B', 'E', 'F', 'P', 'R', 'Z'];
+    check(
+        "day10 assembler round trip",
+        |rng| {
+            let rest = (1..rng.range(1..=8))
+                .map(|_| rng.choose(FONT_4X6.glyphs).0)
+                .collect::<String>();
+            format!("{}{rest}", rng.choose(&FIRST))
+        },
+        no_shrink,
+        |word| {
+            let pixels = text(word).map_err(|e| e.to_string())?;
+            let shortest = assemble(&pixels).map_err(|e| e.to_string())?;
+            let program = assemble_random(&mut Rng::new(word.len() as u64), &pixels)
+                .map_err(|e| e.to_string())?;
+            if program.len() < shortest.len() {
+                return Err(format!(
+                    "random program has {} instructions, the shortest {}",
+                    program.len(),
+                    shortest.len()
+                ));
+            }
+            for program in [&shortest, &program] {
+                verify(program, &pixels).map_err(|e| e.to_string())?;
+            }
+            let mut crt = Crt::standard();
+            Vm::new(registers(), program).run(&mut [&mut crt]);
+            agree(
+                ocr::read(&crt.screen()).map_err(|e| e.to_string())?,
+                word.clone(),
+            )
+        },
+    );
+}
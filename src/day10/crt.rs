@@ -0,0 +1,161 @@
+/// The CRT as a device hanging off the CPU: a screen of any size, scanned one pixel per cycle,
+/// and one or more sprites that each follow a register around. A pixel lights up in the colour
+/// of the first sprite that covers it as it's drawn.
+use std::fmt::Write;
+
+use crate::shared::vm::{Observer, Registers};
+
+#[derive(Debug, Clone)]
+pub(crate) struct Sprite {
+    /// the sprite is centered on this register's value
+    pub register: &'static str,
+    pub width: usize,
+    pub color: [u8; 3],
+}
+
+impl Sprite {
+    /// The puzzle's sprite: 3 pixels wide, following x
+    pub(crate) fn standard() -> Self {
+        Self {
+            register: "x",
+            width: 3,
+            color: [0x00, 0xcc, 0x00],
+        }
+    }
+
+    fn covers(&self, col: i64, registers: &Registers) -> bool {
+        let left = registers.get(self.register) - (self.width as i64 - 1) / 2;
+        (left..left + self.width as i64).contains(&col)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Crt {
+    pub width: usize,
+    pub height: usize,
+    pub sprites: Vec<Sprite>,
+    /// which sprite lit each pixel, row by row
+    pixels: Vec<Option<usize>>,
+}
+
+impl Crt {
+    /// Any size, as long as there's at least one pixel each way
+    pub(crate) fn new(width: usize, height: usize, sprites: Vec<Sprite>) -> Self {
+        assert!(
+            width > 0 && height > 0,
+            "a CRT can't be {width}x{height}, it needs at least one row and one column"
+        );
+        Self {
+            width,
+            height,
+            sprites,
+            pixels: vec![None; width * height],
+        }
+    }
+
+    /// The puzzle's 40x6 screen
+    pub(crate) fn standard() -> Self {
+        Self::new(40, 6, vec![Sprite::standard()])
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[Option<usize>]> {
+        self.pixels.chunks(self.width)
+    }
+
+    /// Lines of `#` and `.`
+    pub(crate) fn screen(&self) -> String {
+        self.rows()
+            .map(|row| {
+                let mut line = row
+                    .iter()
+                    .map(|p| if p.is_some() { '#' } else { '.' })
+                    .collect::<String>();
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+
+    /// Binary PPM image, each pixel blown up to a `scale` x `scale` square
+    pub(crate) fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let (width, height) = (self.width * scale, self.height * scale);
+        let mut ppm = format!("P6\n{width} {height}\n255\n").into_bytes();
+        for row in self.rows() {
+            for _ in 0..scale {
+                for pixel in row {
+                    let color = pixel.map_or([0; 3], |s| self.sprites[s].color);
+                    for _ in 0..scale {
+                        ppm.extend_from_slice(&color);
+                    }
+                }
+            }
+        }
+        ppm
+    }
+
+    /// Full blocks in each sprite's colour, with ANSI escapes
+    pub(crate) fn to_terminal(&self) -> String {
+        let mut out = String::new();
+        for row in self.rows() {
+            for pixel in row {
+                match pixel {
+                    Some(s) => {
+                        let [r, g, b] = self.sprites[*s].color;
+                        write!(out, "\x1b[38;2;{r};{g};{b}m\u{2588}\u{2588}").unwrap();
+                    }
+                    None => out.push_str("\x1b[0m  "),
+                }
+            }
+            out.push_str("\x1b[0m\n");
+        }
+        out
+    }
+}
+
+impl Observer for Crt {
+    fn on_cycle(&mut self, cycle: u64, registers: &Registers) {
+        // anything drawn after the last pixel is lost
+        let Some(pixel) = self.pixels.get_mut(cycle as usize - 1) else {
+            return;
+        };
+        let col = (cycle as i64 - 1) % self.width as i64;
+        *pixel = self.sprites.iter().position(|s| s.covers(col, registers));
+    }
+}
+
+#[test]
+fn test_custom_geometry() {
+    let mut registers = Registers::new(&[("x", 0), ("y", 4)]);
+    let wide = Sprite {
+        register: "y",
+        width: 4,
+        color: [255, 0, 0],
+    };
+    let mut crt = Crt::new(6, 2, vec![Sprite::standard(), wide]);
+    for cycle in 1..=14 {
+        if cycle == 7 {
+            registers.set("x", 5);
+        }
+        crt.on_cycle(cycle, &registers);
+    }
+    // x covers -1..=1 then 4..=6, the 4 wide sprite on y covers 3..=6
+    assert_eq!(crt.screen(), "##.###\n...###\n");
+    assert_eq!(crt.pixels[3], Some(1));
+    assert_eq!(crt.pixels[10], Some(0));
+
+    let ppm = crt.to_ppm(2);
+    let header = b"P6\n12 4\n255\n";
+    assert_eq!(&ppm[..header.len()], header);
+    assert_eq!(ppm.len(), header.len() + 12 * 4 * 3);
+    // second pixel of the first row, scaled up, still the standard sprite's green
+    assert_eq!(
+        &ppm[header.len() + 6..header.len() + 9],
+        &[0x00, 0xcc, 0x00]
+    );
+}
+
+#[test]
+#[should_panic(expected = "a CRT can't be 0x6")]
+fn test_zero_width() {
+    Crt::new(0, 6, vec![Sprite::standard()]);
+}
@@ -0,0 +1,25 @@
+/// Random programs that draw a random 8 letter word on the CRT, like the real puzzle.
+use super::asm::{self, AsmError};
+use crate::shared::{ocr::FONT_4X6, rng::Rng};
+
+const LETTERS: usize = 8;
+
+pub(crate) fn generate(rng: &mut Rng, _size: usize) -> String {
+    // x starts at 1, which rules out some letters in the top left corner
+    loop {
+        let word = (0..LETTERS)
+            .map(|_| rng.choose(FONT_4X6.glyphs).0)
+            .collect::<String>();
+        let pixels = asm::text(&word).unwrap_or_else(|e| panic!("{e}"));
+        match asm::assemble_random(rng, &pixels) {
+            Ok(program) => return asm::program_text(&program),
+            Err(AsmError::Undrawable) => continue,
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    assert!(super::parse_input(input.as_bytes()).count() > 0);
+}
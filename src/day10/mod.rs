@@ -1,19 +1,33 @@
-use std::collections::VecDeque;
+use std::{
+    fmt::{self, Display},
+    io::BufRead,
+};
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::{all_consuming, map, value},
+    combinator::{map, value},
     sequence::preceded,
-    Finish, IResult,
+    IResult,
+};
+
+use crate::shared::{
+    input, ocr,
+    parse::{parse_all, signed},
+    vm::{self, Breakpoint, Debugger, Observer, Registers, Vm},
 };
 
-use crate::shared::read_lines;
+pub(crate) mod asm;
+pub(crate) mod crt;
+pub(crate) mod generate;
 
-const TARGET_CYCLES_PT1: [u32; 6] = [20, 60, 100, 140, 180, 220];
+use crt::Crt;
+
+const DEBUG: bool = false;
+const TARGET_CYCLES_PT1: [u64; 6] = [20, 60, 100, 140, 180, 220];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Instruction {
+pub(crate) enum Instruction {
     Noop,
     Addx(i32),
 }
@@ -21,103 +35,84 @@ enum Instruction {
 impl Instruction {
     fn parse(i: &str) -> IResult<&str, Self> {
         let noop = tag("noop");
-        let addx = preceded(tag("addx "), nom::character::complete::i32);
+        let addx = preceded(tag("addx "), signed);
         alt((value(Self::Noop, noop), map(addx, Self::Addx)))(i)
     }
 }
 
-#[derive(Debug)]
-struct StateMachine {
-    instructions: VecDeque<Instruction>,
-    rx: i32,
-    cycle: u32,
-    signal_strengths: Vec<i32>,
-    display: [u8; 240],
-}
-
-impl std::fmt::Display for StateMachine {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "cycle: {}, rx: {}", self.cycle, self.rx)
+impl Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Noop => write!(f, "noop"),
+            Self::Addx(val) => write!(f, "addx {val}"),
+        }
     }
 }
 
-impl StateMachine {
-    fn new(instructions: VecDeque<Instruction>) -> Self {
-        Self {
-            instructions,
-            rx: 1,
-            cycle: 0,
-            signal_strengths: Vec::new(),
-            display: [b'.'; 240],
+impl vm::Instruction for Instruction {
+    fn cycles(&self) -> u32 {
+        match self {
+            Self::Noop => 1,
+            Self::Addx(_) => 2,
         }
     }
 
-    fn process(&mut self) {
-        while let Some(instruction) = self.instructions.pop_front() {
-            self.cycle += 1;
-            self.check_cycle();
-
-            match instruction {
-                Instruction::Addx(val) => {
-                    self.cycle += 1;
-                    self.check_cycle();
-
-                    self.rx += val;
-                }
-                Instruction::Noop => {}
-            };
+    fn execute(&self, registers: &mut Registers) {
+        if let Self::Addx(val) = self {
+            registers.set("x", registers.get("x") + *val as i64);
         }
     }
+}
 
-    fn check_cycle(&mut self) {
-        if TARGET_CYCLES_PT1.contains(&self.cycle) {
-            self.signal_strengths.push(self.cycle as i32 * self.rx);
-        }
-        self.draw_to_crt()
-    }
+fn registers() -> Registers {
+    Registers::new(&[("x", 1)])
+}
 
-    fn draw_to_crt(&mut self) {
-        let cur_pix = (self.cycle - 1) % 40;
-        let sprite_pos = (self.rx - 1)..=(self.rx + 1);
-        match sprite_pos.contains(&(cur_pix as i32)) {
-            true => self.display[self.cycle as usize - 1] = b'#',
-            false => {}
-        }
-    }
+/// Adds up cycle * x during the cycles part 1 asks about
+#[derive(Debug, Default)]
+struct SignalStrength {
+    total: i64,
+}
 
-    fn show(&self) {
-        for crt_line in self.display.array_chunks::<40>() {
-            crt_line.iter().for_each(|c| print!("{} ", char::from(*c)));
-            println!();
+impl Observer for SignalStrength {
+    fn on_cycle(&mut self, cycle: u64, registers: &Registers) {
+        if TARGET_CYCLES_PT1.contains(&cycle) {
+            self.total += cycle as i64 * registers.get("x");
         }
     }
 }
 
-fn parse_input(path: &str) -> Result<VecDeque<Instruction>, std::io::Error> {
-    let input_lines = read_lines(path).expect("error reading input file");
-    Ok(input_lines
-        .map(|l| {
-            if let Ok(line) = l {
-                all_consuming(Instruction::parse)(line.as_str())
-                    .finish()
-                    .unwrap()
-                    .1
-            } else {
-                panic!("error parsing instruction from line")
-            }
+/// Parse instructions one line at a time
+fn parse_input(reader: impl BufRead) -> impl Iterator<Item = Instruction> {
+    input::stream_lines(reader)
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(n, line)| {
+            parse_all(line.trim(), n + 1, &mut Instruction::parse).unwrap_or_else(|e| panic!("{e}"))
         })
-        .collect())
+}
+
+/// Stop at each of part 1's cycles to show x, then dump the whole register history
+fn trace() {
+    let mut debugger = Debugger::new(Vm::new(registers(), parse_input(input::open(10))));
+    debugger.breakpoints = TARGET_CYCLES_PT1.map(Breakpoint::Cycle).to_vec();
+    while let Some(breakpoint) = debugger.resume(&mut []) {
+        println!("{breakpoint}: x = {}", debugger.vm.registers.get("x"));
+    }
+    print!("{}", debugger.history_csv());
 }
 
 pub fn run() {
     println!("\n=== Day 10 ===");
-    let instructions = parse_input("src/day10/input.txt").expect("error parsing input");
-    let mut machine = StateMachine::new(instructions.clone());
-    machine.process();
-    println!("Part 1: {}", machine.signal_strengths.iter().sum::<i32>());
-
-    let mut display = StateMachine::new(instructions);
-    display.process();
-    println!("Part 2:");
-    display.show();
+    let mut signal = SignalStrength::default();
+    let mut crt = Crt::standard();
+    Vm::new(registers(), parse_input(input::open(10))).run(&mut [&mut signal, &mut crt]);
+    println!("Part 1: {}", signal.total);
+    if DEBUG {
+        trace();
+        print!("{}", crt.to_terminal());
+        std::fs::write("day10.ppm", crt.to_ppm(8)).unwrap();
+    }
+    let letters = ocr::read(&crt.screen()).unwrap_or_else(|e| panic!("{e}"));
+    println!("Part 2: {letters}");
 }
@@ -0,0 +1,200 @@
+/// The arithmetic monkeys do with `old`: `+ - * / %` with the usual precedence, parentheses,
+/// and non-negative integer constants. Relief is written the same way (`old / 3`).
+use std::fmt::{self, Display};
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{self as cc, char, one_of, space0},
+    combinator::{map, value},
+    multi::fold_many0,
+    sequence::{delimited, pair},
+    IResult,
+};
+
+use super::worry::Worry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Op {
+    fn symbol(self) -> char {
+        match self {
+            Op::Add => '+',
+            Op::Sub => '-',
+            Op::Mul => '*',
+            Op::Div => '/',
+            Op::Rem => '%',
+        }
+    }
+
+    fn from_symbol(symbol: char) -> Self {
+        match symbol {
+            '+' => Op::Add,
+            '-' => Op::Sub,
+            '*' => Op::Mul,
+            '/' => Op::Div,
+            '%' => Op::Rem,
+            _ => unreachable!(),
+        }
+    }
+
+    fn precedence(self) -> u8 {
+        match self {
+            Op::Add | Op::Sub => 1,
+            Op::Mul | Op::Div | Op::Rem => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Old,
+    Constant(u64),
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+/// Why an expression has no value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// above what the worry type can hold, or below zero
+    Overflow,
+    DivideByZero,
+}
+
+impl Expr {
+    pub fn eval<W: Worry>(&self, old: &W) -> Result<W, Failure> {
+        match self {
+            Expr::Old => Ok(old.clone()),
+            Expr::Constant(c) => Ok(W::from_u64(*c)),
+            Expr::Binary(l, op, r) => {
+                let (l, r) = (l.eval(old)?, r.eval(old)?);
+                if matches!(op, Op::Div | Op::Rem) && r == W::from_u64(0) {
+                    return Err(Failure::DivideByZero);
+                }
+                match op {
+                    Op::Add => l.checked_add(&r),
+                    Op::Sub => l.checked_sub(&r),
+                    Op::Mul => l.checked_mul(&r),
+                    Op::Div => Some(l.div(&r)),
+                    Op::Rem => Some(l.rem(&r)),
+                }
+                .ok_or(Failure::Overflow)
+            }
+        }
+    }
+
+    /// Whether the result is the same modulo any m when `old` is replaced by `old % m`, which
+    /// only holds for `+` and `*`. (`-` would be too, if worry could go below zero.)
+    pub fn is_modular(&self) -> bool {
+        match self {
+            Expr::Old | Expr::Constant(_) => true,
+            Expr::Binary(l, op, r) => {
+                matches!(op, Op::Add | Op::Mul) && l.is_modular() && r.is_modular()
+            }
+        }
+    }
+
+    /// The part of the expression after `new = `
+    pub fn parse(i: &str) -> IResult<&str, Self> {
+        binary(term, "+-")(i)
+    }
+}
+
+fn term(i: &str) -> IResult<&str, Expr> {
+    binary(factor, "*/%")(i)
+}
+
+fn factor(i: &str) -> IResult<&str, Expr> {
+    delimited(
+        space0,
+        alt((
+            value(Expr::Old, tag("old")),
+            map(cc::u64, Expr::Constant),
+            delimited(char('('), Expr::parse, char(')')),
+        )),
+        space0,
+    )(i)
+}
+
+/// Operands separated by any of `ops`, grouped from the left
+fn binary(
+    operand: fn(&str) -> IResult<&str, Expr>,
+    ops: &'static str,
+) -> impl Fn(&str) -> IResult<&str, Expr> {
+    move |i| {
+        let (i, first) = operand(i)?;
+        fold_many0(
+            pair(one_of(ops), operand),
+            move || first.clone(),
+            |l, (op, r)| Expr::Binary(Box::new(l), Op::from_symbol(op), Box::new(r)),
+        )(i)
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Old => write!(f, "old"),
+            Expr::Constant(c) => write!(f, "{c}"),
+            Expr::Binary(l, op, r) => {
+                // parenthesize whatever would otherwise group differently when read back
+                let needs_parens = |e: &Expr, right: bool| match e {
+                    Expr::Binary(_, inner, _) => {
+                        inner.precedence() < op.precedence()
+                            || (right && inner.precedence() == op.precedence())
+                    }
+                    _ => false,
+                };
+                let side = |f: &mut fmt::Formatter<'_>, e: &Expr, right| {
+                    if needs_parens(e, right) {
+                        write!(f, "({e})")
+                    } else {
+                        write!(f, "{e}")
+                    }
+                };
+                side(f, l, false)?;
+                write!(f, " {} ", op.symbol())?;
+                side(f, r, true)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parse_and_eval() {
+    use nom::{combinator::all_consuming, Finish};
+
+    let parse = |s| all_consuming(Expr::parse)(s).finish().unwrap().1;
+    let cases = [
+        ("old * 19", 190, "old * 19"),
+        ("old*old", 100, "old * old"),
+        ("old + 3 * 2", 16, "old + 3 * 2"),
+        ("(old + 3) * 2", 26, "(old + 3) * 2"),
+        ("old - 2 - 3", 5, "old - 2 - 3"),
+        ("old - (4 - 3 + 2)", 7, "old - (4 - 3 + 2)"),
+        ("( old % 4 ) / 2 + old / 3", 4, "old % 4 / 2 + old / 3"),
+    ];
+    for (text, val, shown) in cases {
+        let expr = parse(text);
+        assert_eq!(expr.eval(&10_u64), Ok(val), "{text}");
+        assert_eq!(expr.to_string(), shown);
+        assert_eq!(parse(shown), expr);
+    }
+
+    assert!(parse("(old + 1) * old").is_modular());
+    assert!(!parse("old / 3").is_modular());
+    assert!(!parse("old * 2 - 1").is_modular());
+    assert_eq!(parse("old - 11").eval(&10_u64), Err(Failure::Overflow));
+    assert_eq!(
+        parse("old % (old - 10)").eval(&10_u64),
+        Err(Failure::DivideByZero)
+    );
+    assert_eq!(parse("old * old").eval(&u64::MAX), Err(Failure::Overflow));
+}
@@ -0,0 +1,48 @@
+/// Random monkey troops. Every test divisor is a different small prime, so squaring a worry level
+/// that's already been reduced modulo their product still fits in a `u64`.
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+const PRIMES: [u64; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let count = (size * 8 / 10).clamp(2, 8);
+    let mut divisors = PRIMES;
+    rng.shuffle(&mut divisors);
+
+    let mut out = String::new();
+    for (id, divisor) in divisors.iter().take(count).enumerate() {
+        if id > 0 {
+            out.push('\n');
+        }
+        let items = (0..rng.range(1..=6))
+            .map(|_| rng.range(50..=99_u64).to_string())
+            .collect::<Vec<_>>();
+        let operation = match rng.range(0..=5) {
+            0 => "old * old".to_string(),
+            1 | 2 => format!("old * {}", rng.range(2..=19)),
+            _ => format!("old + {}", rng.range(1..=8)),
+        };
+        // two different monkeys, neither of them this one
+        let if_true = (id + rng.range(1..=count - 1)) % count;
+        let if_false = loop {
+            let other = rng.index(count);
+            if other != id && (other != if_true || count == 2) {
+                break other;
+            }
+        };
+        writeln!(out, "Monkey {id}:").unwrap();
+        writeln!(out, "  Starting items: {}", items.join(", ")).unwrap();
+        writeln!(out, "  Operation: new = {operation}").unwrap();
+        writeln!(out, "  Test: divisible by {divisor}").unwrap();
+        writeln!(out, "    If true: throw to monkey {if_true}").unwrap();
+        writeln!(out, "    If false: throw to monkey {if_false}").unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::shared::parse_troop(input).unwrap();
+}
@@ -0,0 +1,73 @@
+/// Keep away one item at a time. Items never affect each other: where an item goes only depends
+/// on its own worry level, and it gets inspected in the same round again whenever it's thrown to
+/// a monkey that hasn't had its turn yet. With worry taken modulo the troop's modulus an item only
+/// has so many states it can be in at the start of a round, so it ends up going round in circles,
+/// and any number of rounds can be worked out from the first pass through the circle.
+use super::{expr::Expr, fail, modulus, shared::Monkey, worry::WorryError};
+use crate::shared::cycle::CycleDetector;
+
+/// Inspections by each monkey after `rounds` rounds
+pub fn inspections(monkeys: &[Monkey], rounds: u64, relief: &Expr) -> Result<Vec<u64>, WorryError> {
+    let modulus = modulus(monkeys, relief).ok_or(WorryError::NoModulus)?;
+    let mut totals = vec![0; monkeys.len()];
+    for (start, monkey) in monkeys.iter().enumerate() {
+        for item in monkey.items.iter() {
+            let state = (start, item.worry % modulus);
+            let item_totals = follow(monkeys, state, modulus, rounds, relief)?;
+            for (total, n) in totals.iter_mut().zip(item_totals) {
+                *total += n;
+            }
+        }
+    }
+    Ok(totals)
+}
+
+/// Inspections by each monkey of the item that starts out with `monkey` at `worry`
+fn follow(
+    monkeys: &[Monkey],
+    (mut monkey, mut worry): (usize, u64),
+    modulus: u64,
+    rounds: u64,
+    relief: &Expr,
+) -> Result<Vec<u64>, WorryError> {
+    let mut detector = CycleDetector::new();
+    // after[r][m]: inspections by monkey m in the first r rounds
+    let mut after = vec![vec![0_i64; monkeys.len()]];
+    let mut round = 0;
+    loop {
+        // (only the state matters here, metrics are kept per monkey in `after`)
+        if let Some(cycle) = detector.record((monkey, worry), 0) {
+            let per_monkey = |m: usize| after.iter().map(|counts| counts[m]).collect::<Vec<_>>();
+            return Ok((0..monkeys.len())
+                .map(|m| cycle.extrapolate(&per_monkey(m), rounds as usize) as u64)
+                .collect());
+        }
+        if round == rounds {
+            return Ok(after[round as usize].iter().map(|&n| n as u64).collect());
+        }
+        let mut counts = after[round as usize].clone();
+        loop {
+            counts[monkey] += 1;
+            let operation = &monkeys[monkey].operation;
+            let new_worry = operation
+                .eval(&worry)
+                .map_err(|f| fail(monkey, round + 1, operation, &worry, f))?;
+            let new_worry = relief
+                .eval(&new_worry)
+                .map_err(|f| fail(monkey, round + 1, relief, &new_worry, f))?;
+            let (if_true, if_false) = monkeys[monkey].receivers;
+            let target = if monkeys[monkey].test.holds(&new_worry) {
+                if_true
+            } else {
+                if_false
+            };
+            let next_round = target <= monkey;
+            (monkey, worry) = (target, new_worry % modulus);
+            if next_round {
+                break;
+            }
+        }
+        after.push(counts);
+        round += 1;
+    }
+}
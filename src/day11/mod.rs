@@ -1,59 +1,331 @@
 /// Fully adapted from fasterthanli.me's solution:
 /// https://fasterthanli.me/series/advent-of-code-2022/part-11
+mod expr;
+pub(crate) mod generate;
+mod items;
 mod shared;
-use shared::{parse_monkey, Monkey};
+mod trace;
+mod worry;
+use nom::{combinator::all_consuming, Finish};
+use num_bigint::BigUint;
+
+use crate::shared::input;
+use expr::{Expr, Failure};
+use shared::{parse_troop, Monkey};
+use trace::{Throw, Trace, Watcher};
+use worry::{Worry, WorryError};
+
+const DEBUG: bool = false;
+/// How far to check part 2's modular arithmetic against exact worry levels, which about double
+/// in length every time an item meets `old * old`
+const EXACT_ROUNDS: u64 = 20;
+/// What relief does to worry after each inspection
+const RELIEF_PT1: &str = "old / 3";
+const RELIEF_PT2: &str = "old";
+/// Part 2 again, for a lot longer
+const BIG_ROUNDS: u64 = 1_000_000_000_000;
 
 pub(crate) fn run() {
     println!("\n=== Day 11 ===");
-    let chunks = include_str!("input.txt").split("\n\n");
-    let mut monkeys = vec![];
-    for chunk in chunks {
-        monkeys.push(parse_monkey(chunk).unwrap().1);
+    let monkeys = parse_troop(&input::prepare(11, include_str!("input.txt")))
+        .unwrap_or_else(|e| panic!("{e}"));
+
+    if DEBUG {
+        check_exact(&monkeys, EXACT_ROUNDS);
+        trace(&monkeys);
+    }
+    part1(&monkeys);
+    part2(&monkeys);
+    if DEBUG {
+        let inspected = play(&monkeys, BIG_ROUNDS, &expr(RELIEF_PT2));
+        println!("After {BIG_ROUNDS} rounds: {}", monkey_business(inspected));
+    }
+}
+
+/// Relief, or an operation, written out like `old / 3`
+fn expr(text: &str) -> Expr {
+    all_consuming(Expr::parse)(text)
+        .finish()
+        .unwrap_or_else(|e| panic!("bad expression {text:?}: {e:?}"))
+        .1
+}
+
+/// What worry levels can be taken modulo without changing where any item goes: a multiple of
+/// every test's modulus. There's none if a test has no modulus, or any operation (including
+/// relief) doesn't work modulo anything.
+fn modulus<W>(monkeys: &[Monkey<W>], relief: &Expr) -> Option<u64> {
+    if !relief.is_modular() || !monkeys.iter().all(|m| m.operation.is_modular()) {
+        return None;
     }
+    monkeys.iter().try_fold(1, |lcm, m| {
+        let modulus = m.test.modulus()?;
+        (lcm / gcd(lcm, modulus)).checked_mul(modulus)
+    })
+}
 
-    part1(monkeys.clone());
-    part2(monkeys);
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
 }
 
-fn monkey_around(monkeys: &mut [Monkey], rounds: u64, worried: bool) {
-    let divisor_product = monkeys.iter().map(|m| m.divisor).product::<u64>();
-    for _ in 0..rounds {
+/// Play `rounds` rounds of keep away, telling `watcher` about every throw. With `modular`,
+/// worry levels are kept small using `modulus`, when there is one.
+fn monkey_around<W: Worry>(
+    monkeys: &mut [Monkey<W>],
+    rounds: u64,
+    relief: &Expr,
+    modular: bool,
+    watcher: &mut impl Watcher<W>,
+) -> Result<(), WorryError> {
+    let modulus = modulus(monkeys, relief).filter(|_| modular);
+    for round in 1..=rounds {
         for i in 0..monkeys.len() {
-            let mut cur_monkey = monkeys[i].clone();
-            cur_monkey.num_items_inspected += cur_monkey.items.len() as u64;
-            while let Some(mut item) = cur_monkey.items.pop_front() {
-                item %= divisor_product;
-                let mut new_worry = monkeys[i].operation.eval(item);
-                if !worried {
-                    new_worry /= 3;
-                };
-                if new_worry % cur_monkey.divisor == 0 {
-                    monkeys[cur_monkey.receivers.0].items.push_back(new_worry)
-                } else {
-                    monkeys[cur_monkey.receivers.1].items.push_back(new_worry)
+            let items = std::mem::take(&mut monkeys[i].items);
+            monkeys[i].num_items_inspected += items.len() as u64;
+            let operation = monkeys[i].operation.clone();
+            let (test, (if_true, if_false)) = (monkeys[i].test, monkeys[i].receivers);
+            for mut item in items {
+                if let Some(modulus) = modulus {
+                    item.worry = W::from_u64(item.worry.rem_u64(modulus));
                 }
+                let new_worry = operation
+                    .eval(&item.worry)
+                    .map_err(|f| fail(i, round, &operation, &item.worry, f))?;
+                let new_worry = relief
+                    .eval(&new_worry)
+                    .map_err(|f| fail(i, round, relief, &new_worry, f))?;
+                let target = if test.holds(&new_worry) {
+                    if_true
+                } else {
+                    if_false
+                };
+                watcher.on_throw(&Throw {
+                    round,
+                    monkey: i,
+                    item: item.id,
+                    old: item.worry,
+                    new: new_worry.clone(),
+                    target,
+                });
+                item.worry = new_worry;
+                monkeys[target].items.push_back(item);
             }
-            cur_monkey.items.clear();
-            monkeys[i] = cur_monkey;
         }
+        watcher.on_round(round, monkeys);
+    }
+    Ok(())
+}
+
+/// The error for `operation` failing on `old`
+fn fail<W: Worry>(
+    monkey: usize,
+    round: u64,
+    operation: &Expr,
+    old: &W,
+    failure: Failure,
+) -> WorryError {
+    let (old, operation) = (old.to_u64(), operation.clone());
+    match failure {
+        Failure::Overflow => WorryError::Overflow {
+            monkey,
+            round,
+            old,
+            operation,
+        },
+        Failure::DivideByZero => WorryError::DivideByZero {
+            monkey,
+            round,
+            old,
+            operation,
+        },
     }
 }
 
-fn monkey_business(monkeys: &[Monkey]) -> u64 {
-    let mut inspected = monkeys
-        .iter()
-        .map(|m| m.num_items_inspected)
-        .collect::<Vec<u64>>();
+/// Wide enough for the inspections after `BIG_ROUNDS`
+fn monkey_business(mut inspected: Vec<u64>) -> u128 {
     inspected.sort_by(|a, b| b.cmp(a));
-    inspected[0] * inspected[1]
+    inspected[0] as u128 * inspected[1] as u128
 }
 
-fn part1(mut monkeys: Vec<Monkey>) {
-    monkey_around(&mut monkeys, 20, false);
-    println!("Part 1: {}", monkey_business(&monkeys));
+fn inspections<W>(monkeys: &[Monkey<W>]) -> Vec<u64> {
+    monkeys.iter().map(|m| m.num_items_inspected).collect()
 }
 
-fn part2(mut monkeys: Vec<Monkey>) {
-    monkey_around(&mut monkeys, 10_000, true);
-    println!("Part 2: {}", monkey_business(&monkeys));
+/// Play part 2 for `rounds` rounds both ways: modulo the divisors, and with exact worry levels
+fn check_exact(monkeys: &[Monkey], rounds: u64) {
+    let relief = expr(RELIEF_PT2);
+    let mut modular = monkeys.to_vec();
+    monkey_around(&mut modular, rounds, &relief, true, &mut ()).unwrap_or_else(|e| panic!("{e}"));
+    let mut exact = monkeys.iter().map(Monkey::with_worry).collect::<Vec<_>>();
+    monkey_around::<BigUint>(&mut exact, rounds, &relief, false, &mut ())
+        .unwrap_or_else(|e| panic!("{e}"));
+    assert_eq!(inspections(&modular), inspections(&exact));
+    println!("Modular and exact worry agree for {rounds} rounds");
+}
+
+/// Part 1 with every throw traced, and the same listings as the worked example
+fn trace(monkeys: &[Monkey]) {
+    let mut monkeys = monkeys.to_vec();
+    let mut trace = Trace::new([1, 20]);
+    monkey_around(&mut monkeys, 20, &expr(RELIEF_PT1), false, &mut trace)
+        .unwrap_or_else(|e| panic!("{e}"));
+    for round in [1, 20] {
+        print!("{}", trace.holdings(round).unwrap());
+        print!("{}", trace.inspected(round));
+    }
+    print!("{}", trace.stats_csv());
+    println!("{}", trace.stats_json());
+}
+
+/// Inspections per monkey after `rounds` rounds. With a modulus every item ends up going in
+/// circles, so they're followed one at a time. Otherwise worry levels can grow without bound,
+/// and they're tracked exactly.
+fn play(monkeys: &[Monkey], rounds: u64, relief: &Expr) -> Vec<u64> {
+    if modulus(monkeys, relief).is_some() {
+        items::inspections(monkeys, rounds, relief).unwrap_or_else(|e| panic!("{e}"))
+    } else {
+        let mut exact = monkeys.iter().map(Monkey::with_worry).collect::<Vec<_>>();
+        monkey_around::<BigUint>(&mut exact, rounds, relief, false, &mut ())
+            .unwrap_or_else(|e| panic!("{e}"));
+        inspections(&exact)
+    }
+}
+
+fn part1(monkeys: &[Monkey]) {
+    let inspected = play(monkeys, 20, &expr(RELIEF_PT1));
+    println!("Part 1: {}", monkey_business(inspected));
+}
+
+fn part2(monkeys: &[Monkey]) {
+    let inspected = play(monkeys, 10_000, &expr(RELIEF_PT2));
+    println!("Part 2: {}", monkey_business(inspected));
+}
+
+#[cfg(test)]
+fn sample() -> Vec<Monkey> {
+    parse_troop(include_str!("sample_input.txt")).unwrap()
+}
+
+#[test]
+fn test_exact_worry_agrees_with_modular() {
+    let monkeys = sample();
+    let no_relief = expr("old");
+    for rounds in [1, 5, 15] {
+        let mut modular = monkeys.clone();
+        monkey_around(&mut modular, rounds, &no_relief, true, &mut ()).unwrap();
+        let mut exact = monkeys.iter().map(Monkey::with_worry).collect::<Vec<_>>();
+        monkey_around::<BigUint>(&mut exact, rounds, &no_relief, false, &mut ()).unwrap();
+        assert_eq!(inspections(&modular), inspections(&exact));
+    }
+    // still exact with relief, where the modular trick doesn't hold in general
+    let mut exact = monkeys.iter().map(Monkey::with_worry).collect::<Vec<_>>();
+    monkey_around::<BigUint>(&mut exact, 20, &expr("old / 3"), false, &mut ()).unwrap();
+    assert_eq!(monkey_business(inspections(&exact)), 10605);
+}
+
+#[test]
+fn test_overflow_is_reported() {
+    let mut monkeys = sample();
+    let err = monkey_around(&mut monkeys, 20, &expr("old"), false, &mut ()).unwrap_err();
+    let WorryError::Overflow {
+        monkey,
+        round,
+        ref operation,
+        ..
+    } = err
+    else {
+        panic!("expected an overflow, got {err}");
+    };
+    assert_eq!(
+        (monkey, round, operation.to_string().as_str()),
+        (0, 13, "old * 19")
+    );
+    assert_eq!(
+        err.to_string(),
+        "monkey 0 overflowed in round 13: new = old * 19 with old = 13988703546165100909"
+    );
+}
+
+#[test]
+fn test_modulus_only_when_safe() {
+    let mut monkeys = sample();
+    assert_eq!(modulus(&monkeys, &expr("old")), Some(23 * 19 * 13 * 17));
+    assert_eq!(modulus(&monkeys, &expr("old / 3")), None);
+    monkeys[1].operation = expr("old + 6 - 1");
+    assert_eq!(modulus(&monkeys, &expr("old")), None);
+}
+
+#[test]
+fn test_richer_monkeys() {
+    let troop = "\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = (old + 2) * (old - 1)
+  Test: not divisible by 5
+    If true: throw to monkey 1
+    If false: throw to monkey 0
+
+Monkey 1:
+  Starting items: 54, 65, 75
+  Operation: new = old % 7 * 50 + old
+  Test: greater than 100
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+    let monkeys = parse_troop(troop).unwrap();
+    assert_eq!(monkeys[0].operation.to_string(), "(old + 2) * (old - 1)");
+    assert_eq!(monkeys[1].test.to_string(), "greater than 100");
+
+    let keep_small = expr("old % 1000 + 1");
+    assert_eq!(modulus(&monkeys, &keep_small), None);
+    let mut plain = monkeys.clone();
+    monkey_around(&mut plain, 50, &keep_small, true, &mut ()).unwrap();
+    let mut exact = monkeys.iter().map(Monkey::with_worry).collect::<Vec<_>>();
+    monkey_around::<BigUint>(&mut exact, 50, &keep_small, false, &mut ()).unwrap();
+    assert_eq!(inspections(&plain), inspections(&exact));
+
+    let mut monkeys = monkeys;
+    monkeys[0].operation = expr("old / (old - 79)");
+    let err = monkey_around(&mut monkeys, 1, &keep_small, true, &mut ()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "monkey 0 divided by zero in round 1: new = old / (old - 79) with old = 79"
+    );
+
+    let err = parse_troop(&troop.replace("not divisible by 5", "divisible by 0")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 4, column 22: value not allowed here, found \"0\"\n      Test: divisible by 0"
+    );
+}
+
+#[test]
+fn test_items_in_circles() {
+    use crate::shared::prop::{check, no_shrink};
+
+    let no_relief = expr("old");
+    let inspected = items::inspections(&sample(), 10_000, &no_relief).unwrap();
+    assert_eq!(monkey_business(inspected), 2713310158);
+    assert!(matches!(
+        items::inspections(&sample(), 20, &expr("old / 3")),
+        Err(WorryError::NoModulus)
+    ));
+
+    check(
+        "day11 items one at a time",
+        |rng| {
+            let size = rng.range(1..=10);
+            let troop = parse_troop(&generate::generate(rng, size)).unwrap();
+            (troop, rng.range(0..=400_u64))
+        },
+        no_shrink,
+        |(troop, rounds)| {
+            let mut monkeys = troop.clone();
+            monkey_around(&mut monkeys, *rounds, &no_relief, true, &mut ()).unwrap();
+            let one_at_a_time = items::inspections(troop, *rounds, &no_relief).unwrap();
+            crate::shared::prop::agree(one_at_a_time, inspections(&monkeys))
+        },
+    );
 }
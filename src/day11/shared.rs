@@ -1,55 +1,120 @@
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display},
+};
+
+use super::{expr::Expr, worry::Worry};
+use crate::shared::parse::{comma_list, parse_blocks, ParseError};
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{self as cc, newline, one_of, space1},
-    combinator::{map, value},
-    multi::separated_list1,
+    character::complete::{self as cc, newline, space1},
+    combinator::{cut, map, verify},
     sequence::{preceded, tuple},
     IResult,
 };
 
-#[derive(Debug, Clone, Copy)]
-pub enum Operation {
-    Mul(Operand, Operand),
-    Add(Operand, Operand),
+/// What a monkey checks to decide who gets the item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Test {
+    DivisibleBy(u64),
+    NotDivisibleBy(u64),
+    LessThan(u64),
+    GreaterThan(u64),
 }
 
-impl Operation {
-    pub fn eval(self, old: u64) -> u64 {
+impl Test {
+    pub fn holds<W: Worry>(self, worry: &W) -> bool {
         match self {
-            Operation::Add(l, r) => l.eval(old) + r.eval(old),
-            Operation::Mul(l, r) => l.eval(old) * r.eval(old),
+            Test::DivisibleBy(d) => worry.rem_u64(d) == 0,
+            Test::NotDivisibleBy(d) => worry.rem_u64(d) != 0,
+            Test::LessThan(n) => *worry < W::from_u64(n),
+            Test::GreaterThan(n) => *worry > W::from_u64(n),
         }
     }
-}
 
-#[derive(Debug, Clone, Copy)]
-pub enum Operand {
-    Old,
-    Constant(u64),
+    /// Taking worry modulo a multiple of this doesn't change the outcome. Comparisons have no
+    /// such modulus.
+    pub fn modulus(self) -> Option<u64> {
+        match self {
+            Test::DivisibleBy(d) | Test::NotDivisibleBy(d) => Some(d),
+            Test::LessThan(_) | Test::GreaterThan(_) => None,
+        }
+    }
+
+    fn parse(i: &str) -> IResult<&str, Self> {
+        let divisor = || cut(verify(cc::u64, |&d| d != 0));
+        alt((
+            map(preceded(tag("divisible by "), divisor()), Test::DivisibleBy),
+            map(
+                preceded(tag("not divisible by "), divisor()),
+                Test::NotDivisibleBy,
+            ),
+            map(preceded(tag("less than "), cc::u64), Test::LessThan),
+            map(preceded(tag("greater than "), cc::u64), Test::GreaterThan),
+        ))(i)
+    }
 }
 
-impl Operand {
-    pub fn eval(self, old: u64) -> u64 {
+impl Display for Test {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Operand::Old => old,
-            Operand::Constant(c) => c,
+            Test::DivisibleBy(d) => write!(f, "divisible by {d}"),
+            Test::NotDivisibleBy(d) => write!(f, "not divisible by {d}"),
+            Test::LessThan(n) => write!(f, "less than {n}"),
+            Test::GreaterThan(n) => write!(f, "greater than {n}"),
         }
     }
 }
 
+/// An item keeps its id from the start of the game, wherever it gets thrown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item<W = u64> {
+    pub id: usize,
+    pub worry: W,
+}
+
 #[derive(Debug, Clone)]
-pub struct Monkey {
-    pub items: VecDeque<u64>,
+pub struct Monkey<W = u64> {
+    pub items: VecDeque<Item<W>>,
     pub num_items_inspected: u64,
-    pub operation: Operation,
-    pub divisor: u64,
+    pub operation: Expr,
+    pub test: Test,
     pub receivers: (usize, usize),
 }
 
-pub fn parse_monkey(txt: &str) -> IResult<&str, Monkey> {
+impl Monkey {
+    /// The same monkey, keeping track of worry as `W`
+    pub fn with_worry<W: Worry>(&self) -> Monkey<W> {
+        Monkey {
+            items: self
+                .items
+                .iter()
+                .map(|item| Item {
+                    id: item.id,
+                    worry: W::from_u64(item.worry),
+                })
+                .collect(),
+            num_items_inspected: self.num_items_inspected,
+            operation: self.operation.clone(),
+            test: self.test,
+            receivers: self.receivers,
+        }
+    }
+}
+
+/// All the monkeys, with items numbered in the order they're listed
+pub fn parse_troop(txt: &str) -> Result<Vec<Monkey>, ParseError> {
+    let mut monkeys = parse_blocks(txt, parse_monkey)?;
+    let items = monkeys.iter_mut().flat_map(|m| m.items.iter_mut());
+    for (id, item) in items.enumerate() {
+        item.id = id;
+    }
+    Ok(monkeys)
+}
+
+fn parse_monkey(txt: &str) -> IResult<&str, Monkey> {
     // Monkey 0:
     //   Starting items: 79, 98
     //   Operation: new = old * 19
@@ -60,50 +125,28 @@ pub fn parse_monkey(txt: &str) -> IResult<&str, Monkey> {
     let (txt, (_, _, items, _)) = tuple((
         space1,
         tag("Starting items: "),
-        separated_list1(tag(", "), cc::u64),
+        comma_list(cc::u64),
         newline,
     ))(txt)?;
     let (txt, (_, _, operation, _)) =
-        tuple((space1, tag("Operation: "), parse_operation, newline))(txt)?;
-    let (txt, (_, _, divisor, _)) =
-        tuple((space1, tag("Test: divisible by "), cc::u64, newline))(txt)?;
+        tuple((space1, tag("Operation: new = "), Expr::parse, newline))(txt)?;
+    let (txt, (_, _, test, _)) = tuple((space1, tag("Test: "), Test::parse, newline))(txt)?;
     let (txt, (_, _, rcv1, _)) =
         tuple((space1, tag("If true: throw to monkey "), cc::u64, newline))(txt)?;
     let (txt, (_, _, rcv2)) = tuple((space1, tag("If false: throw to monkey "), cc::u64))(txt)?;
 
-    let vec_items = VecDeque::from(items);
+    let vec_items = items
+        .into_iter()
+        .map(|worry| Item { id: 0, worry })
+        .collect();
     Ok((
         txt,
         Monkey {
             num_items_inspected: 0,
             items: vec_items,
             operation,
-            divisor,
+            test,
             receivers: (rcv1 as usize, rcv2 as usize),
         },
     ))
 }
-
-fn parse_operation(txt: &str) -> IResult<&str, Operation> {
-    let (txt, (left, op, right)) = preceded(
-        tag("new = "),
-        tuple((
-            parse_operand,
-            preceded(space1, one_of("*+")),
-            preceded(space1, parse_operand),
-        )),
-    )(txt)?;
-    let op = match op {
-        '*' => Operation::Mul(left, right),
-        '+' => Operation::Add(left, right),
-        _ => unreachable!(),
-    };
-    Ok((txt, op))
-}
-
-fn parse_operand(txt: &str) -> IResult<&str, Operand> {
-    alt((
-        value(Operand::Old, tag("old")),
-        map(cc::u64, Operand::Constant),
-    ))(txt)
-}
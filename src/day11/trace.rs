@@ -0,0 +1,201 @@
+/// Watching a game of keep away as it's played: every throw, and the monkeys at the end of every
+/// round. `Trace` keeps all of it, for per-round inspection counts as CSV or JSON, and for the
+/// "After round N" listings the puzzle uses in its worked example.
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use super::{shared::Monkey, worry::Worry};
+
+/// One inspection: the item's worry as the monkey picks it up and once relief has set in, and
+/// who it's thrown to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Throw<W> {
+    pub round: u64,
+    pub monkey: usize,
+    pub item: usize,
+    pub old: W,
+    pub new: W,
+    pub target: usize,
+}
+
+pub trait Watcher<W> {
+    fn on_throw(&mut self, _throw: &Throw<W>) {}
+    /// Called once every monkey has had its turn
+    fn on_round(&mut self, _round: u64, _monkeys: &[Monkey<W>]) {}
+}
+
+/// For when nobody's watching
+impl<W> Watcher<W> for () {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RoundStats {
+    pub round: u64,
+    /// by each monkey during this round
+    pub inspections: Vec<u64>,
+}
+
+pub struct Trace<W> {
+    pub throws: Vec<Throw<W>>,
+    pub rounds: Vec<RoundStats>,
+    /// rounds to keep the monkeys' items after
+    listed: Vec<u64>,
+    holdings: Vec<(u64, String)>,
+    current: Vec<u64>,
+}
+
+impl<W: Worry> Trace<W> {
+    pub fn new(listed: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            throws: vec![],
+            rounds: vec![],
+            listed: listed.into_iter().collect(),
+            holdings: vec![],
+            current: vec![],
+        }
+    }
+
+    /// What everyone's holding after a listed round, like the puzzle's part 1 example
+    pub fn holdings(&self, round: u64) -> Option<&str> {
+        self.holdings
+            .iter()
+            .find(|(r, _)| *r == round)
+            .map(|(_, listing)| listing.as_str())
+    }
+
+    /// Inspections so far after a round, like the puzzle's part 2 example
+    pub fn inspected(&self, round: u64) -> String {
+        let mut totals = vec![];
+        for stats in self.rounds.iter().take_while(|s| s.round <= round) {
+            totals.resize(stats.inspections.len(), 0);
+            for (total, n) in totals.iter_mut().zip(stats.inspections.iter()) {
+                *total += n;
+            }
+        }
+        let mut listing = format!("== After round {round} ==\n");
+        for (monkey, total) in totals.iter().enumerate() {
+            writeln!(listing, "Monkey {monkey} inspected items {total} times.").unwrap();
+        }
+        listing
+    }
+
+    /// `round,monkey 0,monkey 1...` header, then each round's inspections
+    pub fn stats_csv(&self) -> String {
+        let monkeys = self.rounds.first().map_or(0, |s| s.inspections.len());
+        let mut csv = String::from("round");
+        for monkey in 0..monkeys {
+            write!(csv, ",monkey {monkey}").unwrap();
+        }
+        csv.push('\n');
+        for stats in self.rounds.iter() {
+            let counts = stats.inspections.iter().map(u64::to_string);
+            writeln!(
+                csv,
+                "{},{}",
+                stats.round,
+                counts.collect::<Vec<_>>().join(",")
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    pub fn stats_json(&self) -> String {
+        serde_json::to_string(&self.rounds).unwrap()
+    }
+}
+
+impl<W: Worry> Watcher<W> for Trace<W> {
+    fn on_throw(&mut self, throw: &Throw<W>) {
+        if self.current.len() <= throw.monkey {
+            self.current.resize(throw.monkey + 1, 0);
+        }
+        self.current[throw.monkey] += 1;
+        self.throws.push(throw.clone());
+    }
+
+    fn on_round(&mut self, round: u64, monkeys: &[Monkey<W>]) {
+        let mut inspections = std::mem::take(&mut self.current);
+        inspections.resize(monkeys.len(), 0);
+        self.rounds.push(RoundStats { round, inspections });
+        if self.listed.contains(&round) {
+            let mut listing = format!(
+                "After round {round}, the monkeys are holding items with these worry levels:\n"
+            );
+            for (i, monkey) in monkeys.iter().enumerate() {
+                let items = monkey.items.iter().map(|item| item.worry.to_string());
+                writeln!(
+                    listing,
+                    "Monkey {i}: {}",
+                    items.collect::<Vec<_>>().join(", ")
+                )
+                .unwrap();
+            }
+            self.holdings.push((round, listing));
+        }
+    }
+}
+
+#[test]
+fn test_worked_example() {
+    use super::{expr, monkey_around, sample};
+
+    let mut monkeys = sample();
+    let mut trace = Trace::new([1, 20]);
+    monkey_around(&mut monkeys, 20, &expr("old / 3"), false, &mut trace).unwrap();
+    assert_eq!(
+        trace.throws[0],
+        Throw {
+            round: 1,
+            monkey: 0,
+            item: 0,
+            old: 79,
+            new: 500,
+            target: 3
+        }
+    );
+    assert_eq!(
+        trace.holdings(1),
+        Some(
+            "After round 1, the monkeys are holding items with these worry levels:\n\
+             Monkey 0: 20, 23, 27, 26\n\
+             Monkey 1: 2080, 25, 167, 207, 401, 1046\n\
+             Monkey 2: \n\
+             Monkey 3: \n"
+        )
+    );
+    assert!(trace.holdings(20).unwrap().ends_with(
+        "Monkey 0: 10, 12, 14, 26, 34\nMonkey 1: 245, 93, 53, 199, 115\nMonkey 2: \nMonkey 3: \n"
+    ));
+    assert_eq!(trace.holdings(2), None);
+    assert_eq!(
+        trace.inspected(20),
+        "== After round 20 ==\n\
+         Monkey 0 inspected items 101 times.\n\
+         Monkey 1 inspected items 95 times.\n\
+         Monkey 2 inspected items 7 times.\n\
+         Monkey 3 inspected items 105 times.\n"
+    );
+    assert!(trace
+        .stats_csv()
+        .starts_with("round,monkey 0,monkey 1,monkey 2,monkey 3\n1,2,4,3,5\n"));
+    assert!(trace
+        .stats_json()
+        .starts_with(r#"[{"round":1,"inspections":[2,4,3,5]},"#));
+
+    // part 2's example, without relief
+    let mut monkeys = sample();
+    let mut trace = Trace::new([]);
+    monkey_around(&mut monkeys, 20, &expr("old"), true, &mut trace).unwrap();
+    assert_eq!(
+        trace.inspected(1),
+        "== After round 1 ==\n\
+         Monkey 0 inspected items 2 times.\n\
+         Monkey 1 inspected items 4 times.\n\
+         Monkey 2 inspected items 3 times.\n\
+         Monkey 3 inspected items 6 times.\n"
+    );
+    assert!(trace
+        .inspected(20)
+        .contains("Monkey 3 inspected items 103 times."));
+}
@@ -0,0 +1,98 @@
+/// Worry levels the monkeys can juggle: plain `u64`s that report overflow instead of wrapping,
+/// or exact `BigUint`s that never need the modular trick to stay small.
+use std::fmt::{Debug, Display};
+
+use custom_error::custom_error;
+use num_bigint::BigUint;
+
+use super::expr::Expr;
+
+custom_error! {pub WorryError
+    Overflow{monkey: usize, round: u64, old: u64, operation: Expr} =
+        "monkey {monkey} overflowed in round {round}: new = {operation} with old = {old}",
+    DivideByZero{monkey: usize, round: u64, old: u64, operation: Expr} =
+        "monkey {monkey} divided by zero in round {round}: new = {operation} with old = {old}",
+    NoModulus = "worry levels can't be kept small for these monkeys, so items needn't go in circles"
+}
+
+pub trait Worry: Clone + Debug + Display + PartialOrd {
+    fn from_u64(val: u64) -> Self;
+    /// `None` if the result doesn't fit
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    /// `None` below zero
+    fn checked_sub(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+    fn div(&self, divisor: &Self) -> Self;
+    fn rem(&self, divisor: &Self) -> Self;
+    fn rem_u64(&self, divisor: u64) -> u64;
+    /// For error messages, saturating at `u64::MAX`
+    fn to_u64(&self) -> u64;
+}
+
+impl Worry for u64 {
+    fn from_u64(val: u64) -> Self {
+        val
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u64::checked_add(*self, *other)
+    }
+
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        u64::checked_sub(*self, *other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        u64::checked_mul(*self, *other)
+    }
+
+    fn div(&self, divisor: &Self) -> Self {
+        self / divisor
+    }
+
+    fn rem(&self, divisor: &Self) -> Self {
+        self % divisor
+    }
+
+    fn rem_u64(&self, divisor: u64) -> u64 {
+        self % divisor
+    }
+
+    fn to_u64(&self) -> u64 {
+        *self
+    }
+}
+
+impl Worry for BigUint {
+    fn from_u64(val: u64) -> Self {
+        BigUint::from(val)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        (self >= other).then(|| self - other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+
+    fn div(&self, divisor: &Self) -> Self {
+        self / divisor
+    }
+
+    fn rem(&self, divisor: &Self) -> Self {
+        self % divisor
+    }
+
+    fn rem_u64(&self, divisor: u64) -> u64 {
+        (self % divisor).try_into().unwrap()
+    }
+
+    fn to_u64(&self) -> u64 {
+        self.try_into().unwrap_or(u64::MAX)
+    }
+}
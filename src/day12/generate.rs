@@ -0,0 +1,35 @@
+/// A random heightmap that climbs from `a` on the left to `z` on the right. One row is kept
+/// smooth so there's always a path from S to E, the rest get bumps that may or may not be
+/// climbable.
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    // at least one column per letter so the smooth row never climbs more than 1 at a time
+    let width = (size * 16).max(26);
+    let height = (size * 4).max(2);
+    let path_row = rng.index(height);
+
+    let mut out = String::with_capacity((width + 1) * height);
+    for y in 0..height {
+        for x in 0..width {
+            let base = x * 25 / (width - 1);
+            let bump = match y == path_row {
+                true => 0,
+                false => rng.range(0..=2),
+            };
+            let c = match (x, y == path_row) {
+                (0, true) => 'S',
+                (x, true) if x == width - 1 => 'E',
+                _ => (b'a' + (base + bump).min(25) as u8) as char,
+            };
+            out.push(c);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::cell_grid_from_input(input).get_start_end();
+}
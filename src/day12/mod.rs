@@ -1,10 +1,14 @@
-/// Really inefficent way to do Day 12 (I'm sure). Tried to improve by allocating HashMap & VecDequeue only once, but didn't help much.
-use std::{
-    collections::{HashMap, VecDeque},
-    fmt::Debug,
+/// Really inefficent way to do Day 12 (I'm sure). Tried to improve by allocating HashMap &
+/// VecDequeue only once, but didn't help much.
+use std::fmt::Debug;
+
+use crate::shared::{
+    input,
+    search::{bfs, Path},
+    Grid, GridCoord, GridError,
 };
 
-use crate::shared::{Grid, GridCoord, GridError};
+pub(crate) mod generate;
 
 trait BasicDerive: Default + Debug + Clone + Copy {}
 
@@ -63,32 +67,17 @@ impl Grid<Cell> {
         (start, end)
     }
 
-    fn best_first_search(
+    /// Shortest walk from the nearest of `starts` to `end`
+    fn shortest_path(
         &self,
-        start: GridCoord,
+        starts: &[GridCoord],
         end: GridCoord,
-        parent: &mut HashMap<GridCoord, Option<GridCoord>>,
-        queue: &mut VecDeque<GridCoord>,
-    ) -> Option<Vec<GridCoord>> {
-        queue.push_back(start);
-        parent.insert(start, None);
-        while let Some(curr) = queue.pop_front() {
-            if !self.in_bounds(curr) {
-                continue;
-            }
-            if curr == end {
-                queue.clear();
-                break;
-            }
-            let neighbors = self.walkable_neighbors(curr).unwrap();
-            for nbr in neighbors.iter() {
-                if !parent.contains_key(nbr) {
-                    parent.insert(*nbr, Some(curr));
-                    queue.push_back(*nbr);
-                }
-            }
-        }
-        backtrace(parent, start, end)
+    ) -> Option<Path<GridCoord, usize>> {
+        bfs(
+            starts.iter().copied(),
+            |curr| self.walkable_neighbors(*curr).unwrap(),
+            |curr| *curr == end,
+        )
     }
 
     fn get_coords_of_val(&self, val: u8) -> Vec<GridCoord> {
@@ -106,24 +95,6 @@ impl Grid<Cell> {
     }
 }
 
-fn backtrace(
-    parent: &mut HashMap<GridCoord, Option<GridCoord>>,
-    start: GridCoord,
-    end: GridCoord,
-) -> Option<Vec<GridCoord>> {
-    if !parent.contains_key(&end) {
-        return None;
-    }
-    let mut path = Vec::new();
-    path.push(end);
-    while path.last().unwrap() != &start {
-        path.push(parent.get(path.last().unwrap()).unwrap().unwrap());
-    }
-    parent.clear();
-    path.reverse();
-    Some(path)
-}
-
 fn cell_grid_from_input(input: &str) -> Grid<Cell> {
     let width = input.lines().next().unwrap().len();
     let height = input.lines().count();
@@ -143,40 +114,23 @@ fn cell_grid_from_input(input: &str) -> Grid<Cell> {
     }
 }
 
-fn part1(
-    grid: &Grid<Cell>,
-    parent: &mut HashMap<GridCoord, Option<GridCoord>>,
-    queue: &mut VecDeque<GridCoord>,
-) -> usize {
+fn part1(grid: &Grid<Cell>) -> usize {
     let (start, end) = grid.get_start_end();
-    let res = grid.best_first_search(start, end, parent, queue).unwrap();
-    res.len() - 1
+    grid.shortest_path(&[start], end).unwrap().len()
 }
 
-fn part2(
-    grid: &Grid<Cell>,
-    parent: &mut HashMap<GridCoord, Option<GridCoord>>,
-    queue: &mut VecDeque<GridCoord>,
-) -> usize {
+/// Search from every lowest point at once, the path found starts from the closest one
+fn part2(grid: &Grid<Cell>) -> usize {
     let (_, end) = grid.get_start_end();
-    let mut min = usize::MAX;
-    for start in &grid.get_coords_of_val(0) {
-        if let Some(res) = grid.best_first_search(*start, end, parent, queue) {
-            let distance = res.len() - 1;
-            if distance < min {
-                min = distance;
-            }
-        }
-    }
-    min
+    grid.shortest_path(&grid.get_coords_of_val(0), end)
+        .unwrap()
+        .len()
 }
 
 pub fn run() {
     println!("\n=== Day 12 ===");
-    let input = include_str!("input.txt");
-    let grid = cell_grid_from_input(input);
-    let mut parent = HashMap::new();
-    let mut queue = VecDeque::new();
-    println!("Part 1: {}", part1(&grid, &mut parent, &mut queue));
-    println!("Part 2: {}", part2(&grid, &mut parent, &mut queue));
+    let input = input::prepare(12, include_str!("input.txt"));
+    let grid = cell_grid_from_input(&input);
+    println!("Part 1: {}", part1(&grid));
+    println!("Part 2: {}", part2(&grid));
 }
@@ -0,0 +1,35 @@
+/// Random pairs of nested packets
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for pair in 0..size * 15 {
+        if pair > 0 {
+            out.push('\n');
+        }
+        let left = packet(rng, 0);
+        let right = loop {
+            let right = packet(rng, 0);
+            if right != left {
+                break right;
+            }
+        };
+        out.push_str(&format!("{left}\n{right}\n"));
+    }
+    out
+}
+
+pub(super) fn packet(rng: &mut Rng, depth: usize) -> String {
+    let items = (0..rng.range(0..=5))
+        .map(|_| match depth < 4 && rng.chance(0.3) {
+            true => packet(rng, depth + 1),
+            false => rng.range(0..=10).to_string(),
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", items.join(","))
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    assert!(!super::parse(input).is_empty());
+}
@@ -3,6 +3,10 @@ use std::cmp::Ordering;
 
 use itertools::Itertools;
 
+use crate::shared::{input, parse::blocks};
+
+pub(crate) mod generate;
+
 const DEBUG: bool = false;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -29,10 +33,9 @@ impl PartialOrd for Packet {
 }
 
 fn parse(input: &str) -> Vec<[Packet; 2]> {
-    input
-        .split("\n\n")
-        .map(|s| {
-            let split = s.trim().split('\n').collect_vec();
+    blocks(input)
+        .map(|(_, s)| {
+            let split = s.lines().collect_vec();
             [
                 parse_packet_from_value(from_str::<Value>(split[0]).expect("Bad JSON")),
                 parse_packet_from_value(from_str::<Value>(split[1]).expect("Bad JSON")),
@@ -79,8 +82,8 @@ fn part_2(pairs: &[[Packet; 2]]) -> usize {
 
 pub fn run() {
     println!("\n=== Day 13 ===");
-    let input = include_str!("input.txt");
-    let pairs = parse(input);
+    let input = input::prepare(13, include_str!("input.txt"));
+    let pairs = parse(&input);
     if DEBUG {
         for p in &pairs {
             println!("{:?}\n{:?}\n", p[0], p[1]);
@@ -89,3 +92,90 @@ pub fn run() {
     println!("Part 1: {}", part_1(&pairs));
     println!("Part 2: {}", part_2(&pairs));
 }
+
+/// Compares the packets as text, walking both strings together and wrapping a number in
+/// brackets whenever the other side has a list there
+#[cfg(test)]
+fn reference_cmp(left: &str, right: &str) -> Ordering {
+    // 10 is the only two digit number, as a single char it still sorts after the digits
+    let mut left = left.replace("10", "A").chars().collect_vec();
+    let mut right = right.replace("10", "A").chars().collect_vec();
+    let (mut i, mut j) = (0, 0);
+    loop {
+        match (left.get(i).copied(), right.get(j).copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, _) => return Ordering::Less,
+            (_, None) => return Ordering::Greater,
+            (Some(a), Some(b)) if a == b => (i, j) = (i + 1, j + 1),
+            (Some(']'), _) => return Ordering::Less,
+            (_, Some(']')) => return Ordering::Greater,
+            (Some('['), Some(num)) => drop(right.splice(j..=j, ['[', num, ']'])),
+            (Some(num), Some('[')) => drop(left.splice(i..=i, ['[', num, ']'])),
+            (Some(a), Some(b)) => return a.cmp(&b),
+        }
+    }
+}
+
+/// Smaller numbers, or lists with an item dropped or shrunk
+#[cfg(test)]
+fn shrink_value(value: &Value) -> Vec<Value> {
+    match value {
+        Value::Number(n) => (0..n.as_u64().unwrap())
+            .rev()
+            .take(2)
+            .map(Value::from)
+            .collect(),
+        Value::Array(items) => {
+            let mut out = vec![];
+            for i in 0..items.len() {
+                let mut fewer = items.clone();
+                fewer.remove(i);
+                out.push(Value::Array(fewer));
+            }
+            for (i, item) in items.iter().enumerate() {
+                for smaller in shrink_value(item) {
+                    let mut copy = items.clone();
+                    copy[i] = smaller;
+                    out.push(Value::Array(copy));
+                }
+            }
+            out
+        }
+        _ => vec![],
+    }
+}
+
+#[test]
+fn test_packet_cmp_matches_reference() {
+    use crate::shared::prop::{agree, check};
+
+    check(
+        "day13 packet order",
+        |rng| {
+            let left = generate::packet(rng, 0);
+            // identical packets have to come out equal too
+            let right = match rng.chance(0.3) {
+                true => left.clone(),
+                false => generate::packet(rng, 0),
+            };
+            (left, right)
+        },
+        |(left, right)| {
+            let (l, r) = (
+                from_str::<Value>(left).unwrap(),
+                from_str::<Value>(right).unwrap(),
+            );
+            let lefts = shrink_value(&l)
+                .into_iter()
+                .map(|l| (l.to_string(), right.clone()));
+            let rights = shrink_value(&r)
+                .into_iter()
+                .map(|r| (left.clone(), r.to_string()));
+            lefts.chain(rights).collect()
+        },
+        |(left, right)| {
+            let packet = |s: &str| parse_packet_from_value(from_str::<Value>(s).unwrap());
+            agree(packet(left).cmp(&packet(right)), reference_cmp(left, right))
+        },
+    );
+}
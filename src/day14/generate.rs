@@ -0,0 +1,38 @@
+/// Random rock paths under the sand source. They're kept within the fixed size cave, leaving room
+/// below the lowest rock for the part 2 floor.
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+use super::HEIGHT;
+
+const X_RANGE: (i32, i32) = (440, 560);
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let max_y = HEIGHT as i32 - 3;
+    let mut out = String::new();
+    for _ in 0..size * 15 {
+        let (mut x, mut y) = (
+            rng.range(X_RANGE.0 + 10..=X_RANGE.1 - 10),
+            rng.range(10..=max_y),
+        );
+        let mut points = vec![format!("{x},{y}")];
+        let mut horizontal = rng.chance(0.5);
+        for _ in 0..rng.range(1..=5) {
+            let step = rng.range(1..=8) * if rng.chance(0.5) { 1 } else { -1 };
+            match horizontal {
+                true => x = (x + step).clamp(X_RANGE.0, X_RANGE.1),
+                false => y = (y + step).clamp(1, max_y),
+            }
+            horizontal = !horizontal;
+            points.push(format!("{x},{y}"));
+        }
+        writeln!(out, "{}", points.join(" -> ")).unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    let _ = super::shared::Cave::from(input);
+}
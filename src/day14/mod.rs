@@ -1,6 +1,8 @@
+pub(crate) mod generate;
 pub(super) mod parse;
 pub(super) mod shared;
 
+use crate::shared::input;
 use shared::Tile;
 
 pub const WIDTH: usize = 800;
@@ -15,9 +17,9 @@ enum Part {
 
 pub fn run() {
     println!("\n=== Day 14 ===");
-    let input = include_str!("input.txt");
+    let input = input::prepare(14, include_str!("input.txt"));
 
-    let mut cave = shared::Cave::from(input);
+    let mut cave = shared::Cave::from(input.as_str());
     let mut cave2 = cave.clone();
 
     cave.pour_sand(HALFWAY, 0, Part::One);
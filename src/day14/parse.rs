@@ -1,13 +1,18 @@
 use super::shared::Point;
 use super::HALFWAY;
+use crate::shared::parse::{coord_pair, parse_lines, ParseError};
 use itertools::Itertools;
 use std::collections::HashSet;
 
-use nom::{bytes::complete::tag, character::complete as cc, sequence::tuple, IResult};
+use nom::{bytes::complete::tag, combinator::map, multi::separated_list1, IResult};
 
 pub fn parse_pair(p: &str) -> IResult<&str, (usize, usize)> {
-    let (s, (l, _, r)) = tuple((cc::u64, tag(","), cc::u64))(p)?;
-    Ok((s, (normalize(l), r as usize)))
+    map(coord_pair::<u64>, |(l, r)| (normalize(l), r as usize))(p)
+}
+
+/// Every corner of one rock path, e.g. `498,4 -> 498,6 -> 496,6`
+pub fn parse_path(l: &str) -> IResult<&str, Vec<(usize, usize)>> {
+    separated_list1(tag(" -> "), parse_pair)(l)
 }
 
 fn normalize(x: u64) -> usize {
@@ -15,13 +20,13 @@ fn normalize(x: u64) -> usize {
     diff as usize
 }
 
-pub fn parse_line(l: &str) -> Result<Vec<(usize, usize)>, std::io::Error> {
+/// All the rock in the cave, one path per line
+pub fn parse_walls(input: &str) -> Result<Vec<(usize, usize)>, ParseError> {
     let mut walls: HashSet<(usize, usize)> = HashSet::new();
-    let pairs = l.split(" -> ").into_iter().tuple_windows();
-    for (a, b) in pairs {
-        let (_, p1) = parse_pair(a).unwrap_or_else(|e| panic!("error parsing digit pair {e}"));
-        let (_, p2) = parse_pair(b).unwrap_or_else(|e| panic!("error parsing digit pair {e}"));
-        walls.extend(get_wall(p1, p2))
+    for path in parse_lines(input, parse_path)? {
+        for (p1, p2) in path.into_iter().tuple_windows() {
+            walls.extend(get_wall(p1, p2))
+        }
     }
     Ok(walls.into_iter().collect())
 }
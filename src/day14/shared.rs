@@ -1,4 +1,4 @@
-use super::parse::parse_line;
+use super::parse::parse_walls;
 use super::{Part, HEIGHT, WIDTH};
 use crate::shared::GridError;
 use std::fmt;
@@ -45,7 +45,7 @@ impl Cave {
     }
 
     fn get_max_y(data: &[Tile; WIDTH * HEIGHT]) -> Result<usize, GridError> {
-        for (i, row) in data.array_chunks::<WIDTH>().rev().enumerate() {
+        for (i, row) in data.chunks_exact(WIDTH).rev().enumerate() {
             if row.iter().any(|t| *t == Tile::Wall) {
                 return Ok((HEIGHT - i) + 1);
             }
@@ -146,10 +146,9 @@ impl fmt::Debug for Cave {
 impl From<&str> for Cave {
     fn from(input: &str) -> Self {
         let mut cave = Cave::new();
-        input
-            .trim()
-            .lines()
-            .flat_map(|l| parse_line(l).expect("error parsing line"))
+        parse_walls(input.trim())
+            .unwrap_or_else(|e| panic!("{e}"))
+            .into_iter()
             .for_each(|(x, y)| cave.set_tile(x, y, Tile::Wall));
 
         cave.floor_y = Cave::get_max_y(&cave.data).unwrap();
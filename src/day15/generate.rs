@@ -0,0 +1,65 @@
+/// Random sensor fields with exactly one uncovered spot in the part 2 search area.
+///
+/// Sensors sit on a jittered lattice, each reaching far enough to cover its whole lattice cell.
+/// The ones that can see the hidden spot get their range cut just short of it, and four sensors
+/// placed diagonally around the spot cover whatever that cut leaves open.
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+const LIMIT: i64 = 4_000_000;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let cells = (size / 2).clamp(2, 6) as i64;
+    let spacing = LIMIT / cells;
+    let jitter = spacing / 10;
+    let hidden = (rng.range(0..=LIMIT - 1), rng.range(0..=LIMIT - 1));
+
+    let mut sensors = vec![];
+    for i in 0..=cells {
+        for j in 0..=cells {
+            let at = (
+                i * spacing + rng.range(-jitter..=jitter),
+                j * spacing + rng.range(-jitter..=jitter),
+            );
+            let reach = (spacing + 2 * jitter).min(distance(at, hidden) - 1);
+            sensors.push((at, reach));
+        }
+    }
+    // anything the cut sensors gave up is within this distance of the hidden spot
+    let offset = 2 * (spacing + 2 * jitter) + 1;
+    for (dx, dy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+        let at = (hidden.0 + dx * offset, hidden.1 + dy * offset);
+        sensors.push((at, 2 * offset - 1));
+    }
+    rng.shuffle(&mut sensors);
+
+    let mut out = String::new();
+    for ((x, y), reach) in sensors {
+        // the closest beacon is somewhere right on the edge of the sensor's range
+        let along = rng.range(0..=reach);
+        let (sx, sy) = match rng.index(4) {
+            0 => (1, 1),
+            1 => (1, -1),
+            2 => (-1, 1),
+            _ => (-1, -1),
+        };
+        let beacon = (x + sx * along, y + sy * (reach - along));
+        writeln!(
+            out,
+            "Sensor at x={x}, y={y}: closest beacon is at x={}, y={}",
+            beacon.0, beacon.1
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn distance(a: (i64, i64), b: (i64, i64)) -> i64 {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs()
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::parse_input(input).unwrap();
+}
@@ -1,23 +1,27 @@
 use std::collections::HashSet;
 
-use nom::{bytes::complete::tag, character::complete as cc, sequence::tuple, IResult};
-
-fn parse_input(input: &str) -> IResult<&str, Vec<Pair>> {
-    let mut pairs = vec![];
-    for line in input.lines() {
-        let (_, (_, x1, _, y1, _, x2, _, y2)) = tuple((
-            tag("Sensor at x="),
-            cc::i64,
-            tag(", y="),
-            cc::i64,
-            tag(": closest beacon is at x="),
-            cc::i64,
-            tag(", y="),
-            cc::i64,
-        ))(line)?;
-        pairs.push(Pair::new((x1, y1).into(), (x2, y2).into()));
-    }
-    Ok((input, pairs))
+use crate::shared::{
+    input,
+    parse::{parse_lines, xy_pair, ParseError},
+    IntervalSet,
+};
+use nom::{bytes::complete::tag, combinator::map, sequence::tuple};
+
+pub(crate) mod generate;
+
+fn parse_input(input: &str) -> Result<Vec<Pair>, ParseError> {
+    parse_lines(
+        input,
+        map(
+            tuple((
+                tag("Sensor at "),
+                xy_pair,
+                tag(": closest beacon is at "),
+                xy_pair,
+            )),
+            |(_, sensor, _, beacon)| Pair::new(sensor.into(), beacon.into()),
+        ),
+    )
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -56,10 +60,6 @@ impl std::ops::Sub<Point> for Point {
 }
 
 impl Point {
-    pub fn new(x: i64, y: i64) -> Self {
-        Self { x, y }
-    }
-
     pub fn manhattan_dist(&self, other: &Point) -> i64 {
         (self.x - other.x).abs() + (self.y - other.y).abs()
     }
@@ -81,131 +81,105 @@ impl Pair {
         }
     }
 
+    /// The stretch of row `y` within range of the sensor, if it reaches that far
     pub fn get_xrange_withy(&self, y: i64) -> Option<(i64, i64)> {
-        let rng = (self.sensor.y - self.manhattan_dist)..(self.sensor.y + self.manhattan_dist);
-        if !rng.contains(&y) {
-            return None;
-        }
-
-        let left_corner = Point::new(self.sensor.x - self.manhattan_dist, self.sensor.y);
-        let right_corner = Point::new(self.sensor.x + self.manhattan_dist, self.sensor.y);
-        let top_corner = if y < self.sensor.y {
-            (self.sensor.x, self.sensor.y - self.manhattan_dist).into()
-        } else {
-            (self.sensor.x, self.sensor.y + self.manhattan_dist).into()
-        };
-        // first line
-        let m1 = get_slope(top_corner, left_corner);
-        let b1 = get_y_intercept(left_corner, m1);
-        let left_x = get_x_from_line(y, m1, b1);
-
-        // second_line
-        let m2 = get_slope(top_corner, right_corner);
-        let b2 = get_y_intercept(right_corner, m2);
-        let right_x = get_x_from_line(y, m2, b2);
-
-        Some((left_x, right_x))
+        // the diamond's half-width on this row, it's a single point at the top and bottom tips
+        let half_width = self.manhattan_dist - (y - self.sensor.y).abs();
+        (half_width >= 0).then(|| (self.sensor.x - half_width, self.sensor.x + half_width))
     }
 }
 
-fn get_slope(pt1: Point, pt2: Point) -> i64 {
-    (pt2.y - pt1.y) / (pt2.x - pt1.x)
-}
-
-fn get_y_intercept(pt: Point, slope: i64) -> i64 {
-    pt.y - slope * pt.x
-}
-
-fn get_x_from_line(y: i64, m: i64, b: i64) -> i64 {
-    (y - b) / m
+/// Every x covered by some sensor on row `y`
+fn covered_row(pairs: &[Pair], y: i64) -> IntervalSet<i64> {
+    pairs
+        .iter()
+        .filter_map(|pair| pair.get_xrange_withy(y))
+        .collect()
 }
 
-fn get_empty_row(pairs: &Vec<Pair>, y: i64) -> i64 {
-    let mut min_x = i64::MAX;
-    let mut max_x = 0;
-    for pair in pairs {
-        if let Some((min, max)) = pair.get_xrange_withy(y) {
-            if min < min_x {
-                min_x = min
-            };
-            if max > max_x {
-                max_x = max
-            };
-        }
-    }
+fn get_empty_row(pairs: &[Pair], y: i64) -> i64 {
+    let covered = covered_row(pairs, y);
+    // beacons already sitting on the row don't count as empty
     let beacons: HashSet<i64> = pairs
         .iter()
-        .flat_map(|p| {
-            if p.beacon.y == y && p.beacon.x <= max_x && p.beacon.x >= min_x {
-                Some(p.beacon.x)
-            } else {
-                None
-            }
-        })
+        .filter(|p| p.beacon.y == y && covered.contains(p.beacon.x))
+        .map(|p| p.beacon.x)
         .collect();
-    let mut res = max_x - min_x - beacons.iter().len() as i64;
-    // need to include '0' as point
-    if min_x < 0 {
-        res += 1;
-    }
-    res
+    covered.coverage() - beacons.len() as i64
 }
 
-pub fn part2(pairs: &Vec<Pair>, limit: i64) -> Option<u64> {
-    let mut ranges = Vec::with_capacity(100);
-    let mut stack = Vec::with_capacity(100);
-    for y in 0..limit {
-        for pair in pairs {
-            if let Some((mut left_x, mut right_x)) = pair.get_xrange_withy(y) {
-                if left_x < 0 {
-                    left_x = 0;
-                };
-                if right_x > limit {
-                    right_x = limit;
-                };
-                ranges.push((left_x, right_x));
-            }
-        }
-        ranges.sort_by(|a, b| a.0.cmp(&b.0));
-        stack.push(ranges[0]);
-        for rng in ranges.iter().skip(1) {
-            let last = stack.last().unwrap();
-            let stack_len = stack.len();
-            if last.0 <= rng.0 && rng.0 <= last.1 {
-                stack[stack_len - 1] = (last.0, std::cmp::max(last.1, rng.1));
-            } else {
-                stack.push(*rng);
-            }
-        }
-        if stack.len() > 1 {
-            for window in stack.windows(2) {
-                let (a, b) = (window[0], window[1]);
-                let overlapping = non_overlapping(a, b);
-                if overlapping == 2 {
-                    return Some((b.0 - 1) as u64 * 4_000_000 + y as u64);
-                }
-            }
-        }
-        ranges.clear();
-        stack.clear();
-    }
-    None
-}
-
-pub fn non_overlapping(rng1: (i64, i64), rng2: (i64, i64)) -> i64 {
-    if rng2.0 < rng1.1 {
-        return 0;
-    }
-    rng2.0 - rng1.1
+/// Find the only row with a gap in coverage between 0 and `limit`
+pub fn part2(pairs: &[Pair], limit: i64) -> Option<u64> {
+    (0..limit).find_map(|y| {
+        covered_row(pairs, y)
+            .gaps_within(0, limit)
+            .next()
+            .map(|(x, _)| x as u64 * 4_000_000 + y as u64)
+    })
 }
 
 pub fn run() {
     println!("\n=== Day 15 ===");
-    let input = include_str!("input.txt");
-    let pairs = parse_input(input).unwrap().1;
+    let input = input::prepare(15, include_str!("input.txt"));
+    let pairs = parse_input(&input).unwrap_or_else(|e| panic!("{e}"));
 
     println!("Part 1: {}", get_empty_row(&pairs, 2_000_000));
 
     let tuning_frequency = part2(&pairs, 4_000_000).unwrap();
     println!("Part 2: {tuning_frequency:?}");
 }
+
+/// Is `point` within range of any sensor?
+#[cfg(test)]
+fn naive_covered(pairs: &[Pair], point: Point) -> bool {
+    pairs
+        .iter()
+        .any(|p| p.sensor.manhattan_dist(&point) <= p.manhattan_dist)
+}
+
+#[test]
+fn test_rows_match_per_cell_check() {
+    use crate::shared::prop::{agree, check, no_shrink, shrink_int, shrink_vec};
+
+    const LIMIT: i64 = 20;
+    check(
+        "day15 coverage",
+        |rng| {
+            let pairs = (0..rng.range(1..=6))
+                .map(|_| {
+                    let sensor =
+                        Point::from((rng.range(-5..=LIMIT + 5), rng.range(-5..=LIMIT + 5)));
+                    // a sensor is never sitting right on its beacon
+                    let offset = loop {
+                        let offset = Point::from((rng.range(-12..=12), rng.range(-12..=12)));
+                        if offset != Point::from((0, 0)) {
+                            break offset;
+                        }
+                    };
+                    Pair::new(sensor, sensor + offset)
+                })
+                .collect::<Vec<_>>();
+            (pairs, rng.range(-5..=LIMIT + 5))
+        },
+        |(pairs, y)| {
+            let fewer = shrink_vec(pairs, no_shrink).into_iter().map(|p| (p, *y));
+            let rows = shrink_int(y).into_iter().map(|y| (pairs.clone(), y));
+            fewer.filter(|(p, _)| !p.is_empty()).chain(rows).collect()
+        },
+        |(pairs, y)| {
+            let beacons = pairs.iter().map(|p| p.beacon).collect::<HashSet<_>>();
+            let naive_empty = (-50..=LIMIT + 50)
+                .map(|x| Point::from((x, *y)))
+                .filter(|&pt| naive_covered(pairs, pt) && !beacons.contains(&pt))
+                .count();
+            agree(get_empty_row(pairs, *y), naive_empty as i64)?;
+
+            let naive_gap = (0..LIMIT).find_map(|y| {
+                (0..=LIMIT)
+                    .find(|&x| !naive_covered(pairs, Point::from((x, y))))
+                    .map(|x| x as u64 * 4_000_000 + y as u64)
+            });
+            agree(part2(pairs, LIMIT), naive_gap)
+        },
+    );
+}
@@ -0,0 +1,74 @@
+/// Random valve networks: a connected web of tunnels out of `AA`, with only a handful of the
+/// valves worth opening like in the real puzzle
+use std::{collections::BTreeSet, fmt::Write};
+
+use crate::shared::rng::Rng;
+
+use super::START;
+
+/// Part 2 keeps a bitset of opened valves per state, any more than this gets slow
+const MAX_FLOWING: usize = 15;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let count = (size * 6).clamp(3, 26 * 26);
+    let flowing = (size * 3 / 2).clamp(1, MAX_FLOWING.min(count - 1));
+
+    let mut names = vec![START.to_string()];
+    while names.len() < count {
+        let name = [rng.range(b'A'..=b'Z'), rng.range(b'A'..=b'Z')];
+        let name = String::from_utf8(name.to_vec()).unwrap();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    // a random tree keeps everything reachable, then a few extra tunnels for loops
+    let mut tunnels = vec![BTreeSet::new(); count];
+    for i in 1..count {
+        let j = rng.index(i);
+        tunnels[i].insert(j);
+        tunnels[j].insert(i);
+    }
+    for _ in 0..count / 3 {
+        let (i, j) = (rng.index(count), rng.index(count));
+        if i != j {
+            tunnels[i].insert(j);
+            tunnels[j].insert(i);
+        }
+    }
+
+    let mut flows = vec![0; count];
+    let mut picks = (1..count).collect::<Vec<_>>();
+    rng.shuffle(&mut picks);
+    for &i in picks.iter().take(flowing) {
+        flows[i] = rng.range(3..=25);
+    }
+
+    let mut order = (0..count).collect::<Vec<_>>();
+    rng.shuffle(&mut order);
+    let mut out = String::new();
+    for i in order {
+        let leads = tunnels[i]
+            .iter()
+            .map(|&j| names[j].as_str())
+            .collect::<Vec<_>>();
+        let tunnel = match leads.len() {
+            1 => "tunnel leads to valve",
+            _ => "tunnels lead to valves",
+        };
+        writeln!(
+            out,
+            "Valve {} has flow rate={}; {tunnel} {}",
+            names[i],
+            flows[i],
+            leads.join(", ")
+        )
+        .unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::parse_input(input).unwrap();
+}
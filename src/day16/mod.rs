@@ -1,20 +1,23 @@
-use std::{
-    cmp::Ordering,
-    collections::{BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque},
-};
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{self as cc, alpha1},
-    multi::separated_list0,
-    sequence::tuple,
-    Finish, IResult,
+    branch::alt, bytes::complete::tag, character::complete::alpha1, sequence::tuple, IResult,
+};
+
+use crate::shared::{
+    graph::Graph,
+    input,
+    parse::{comma_list, parse_lines, unsigned, ParseError},
+    search::optimize::{maximize, Problem},
+    BitSet64, Interner,
 };
 
+pub(crate) mod generate;
+
 const PART1_TIME_LIMIT: u32 = 30;
 const PART2_TIME_LIMIT: u32 = 26;
+const START: &str = "AA";
 
 #[derive(Debug)]
 pub struct Valve<'a> {
@@ -22,284 +25,197 @@ pub struct Valve<'a> {
     pub adjacent: HashSet<&'a str>,
 }
 
-#[derive(PartialEq, Eq)]
-struct Node<'a> {
-    cost: u32,
-    curr: &'a str,
-}
-
-impl<'a> Ord for Node<'a> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
-    }
-}
-
-impl<'a> PartialOrd for Node<'a> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-/// Get lowest cost to move from a valve to another valve
-fn min_cost(from: &str, to: &str, map: &HashMap<&str, Valve>) -> u32 {
-    // shortest path:
-    // Dijkstra's algorithm
-    // nodes in the priority queue are sorted so the lowest cost gets popped first
-    let mut pq = BinaryHeap::new();
-    // prevent backtracking by keeping track of seen valve rooms
-    let mut seen = HashSet::new();
-
-    pq.push(Node {
-        cost: 0,
-        curr: from,
-    });
-    seen.insert(from);
-
-    while let Some(Node { cost, curr }) = pq.pop() {
-        if curr == to {
-            return cost;
-        }
-        for next in map[curr].adjacent.iter() {
-            // HashSet returns `true` if item was not already in the set
-            if seen.insert(next) {
-                pq.push(Node {
-                    cost: cost + 1,
-                    curr: next,
-                });
-            }
+/// Squash the tunnel map down to the start valve plus the flowing valves.
+/// Every edge in the result is the number of minutes it takes to walk between two of them.
+fn min_distances<'a>(map: &HashMap<&'a str, Valve<'a>>, start: &'a str) -> Graph<&'a str, u32> {
+    let mut tunnels = Graph::directed();
+    for (&name, valve) in map.iter() {
+        // every tunnel takes 1 minute
+        for &next in valve.adjacent.iter() {
+            tunnels.add_edge(name, next, 1);
         }
     }
-    u32::MAX
-}
-
-/// map shortest distance from "AA" to any flowing valve
-/// map shortest distance from any flowing valve to any other
-fn min_distances<'a>(map: &'a HashMap<&str, Valve>) -> HashMap<(&'a str, &'a str), u32> {
-    map.iter()
-        // only keep flowing valves
-        .filter(|(_, valve)| valve.flow > 0)
-        // get names of those valves
-        .map(|(&name, _)| name)
-        // iter over every combo of 2 flowing valves
-        .tuple_combinations()
-        // record shorted distance between those 2
-        // (and the dist from "AA" to flowing valve bc that is the starting point)
-        .fold(HashMap::new(), |mut acc, (name1, name2)| {
-            // from AA to name1
-            acc.entry(("AA", name1))
-                .or_insert_with(|| min_cost("AA", name1, map));
-            // from AA to name2
-            acc.entry(("AA", name2))
-                .or_insert_with(|| min_cost("AA", name2, map));
-            let dist = min_cost(name1, name2, map);
-            // from name1 to name2
-            acc.insert((name1, name2), dist);
-            // from name2 to name1
-            acc.insert((name2, name1), dist);
-
-            acc
-        })
+    tunnels.contract(&start, |name| map[name].flow > 0)
 }
 
 /// Contain current state of open valves for simulation
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
 struct State<'a> {
-    opened: BTreeSet<&'a str>,
+    /// bit indices from `flowing_valves`
+    opened: BitSet64,
     curr: &'a str,
     elapsed: u32,
+    /// everything the opened valves will relieve by the time limit
     relieved: u32,
+    /// we're done, the elephant is going round the valves we left closed
+    elephant: bool,
 }
 
-/// All flowing valves are open, wait until time limit is reached
-fn wait_until_times_up(
-    max_time: u32,
-    elapsed: u32,
-    relieved: u32,
-    opened: &BTreeSet<&str>,
-    map: &HashMap<&str, Valve>,
-) -> u32 {
-    let time_left = max_time - elapsed;
-    let relieved_per_minute: u32 = opened.iter().map(|name| &map[name].flow).sum();
-    relieved + (relieved_per_minute * time_left)
-}
-
-pub fn part1(input: &str) -> u32 {
-    let map = parse_input(input).finish().unwrap().1;
-    let dist_map = min_distances(&map); // key: (from, to), value: move_cost
-    let flowing: HashSet<_> = map
+/// Give every flowing valve a bit index (sorted by name so the numbering is stable),
+/// and return the flow rate for each index
+fn flowing_valves<'a>(map: &HashMap<&'a str, Valve>) -> (Interner<&'a str>, Vec<u32>) {
+    let flowing: Interner<&str> = map
         .iter()
         .filter(|(_, valve)| valve.flow > 0)
         .map(|(&name, _)| name)
+        .sorted()
         .collect();
+    assert!(
+        flowing.len() <= 64,
+        "too many flowing valves for a BitSet64"
+    );
+    let flows = flowing.keys().iter().map(|name| map[name].flow).collect();
+    (flowing, flows)
+}
 
-    let mut max_relieved = 0;
-    let mut q = VecDeque::new();
-    let mut seen = HashSet::new();
-
-    q.push_back(State {
-        curr: "AA",
-        opened: BTreeSet::new(),
-        elapsed: 0,
-        relieved: 0,
-    });
-    // current position doesn't matter for `seen`
-    seen.insert((BTreeSet::new(), 0, 0));
+/// A branch-and-bound search: move to an unopened valve and open it, or stop and wait. With an
+/// elephant along, stopping can also hand over to the elephant, which starts out from `START` at
+/// minute 0 with whatever valves are still closed.
+///
+/// A valve is credited with everything it will relieve as soon as it's opened, so where a state
+/// is going only depends on its valves, position, time and whose turn it is.
+struct ValveSearch<'a, 'm> {
+    dist_map: &'m Graph<&'a str, u32>,
+    flowing: &'m Interner<&'a str>,
+    flows: &'m [u32],
+    time_limit: u32,
+    with_elephant: bool,
+}
 
-    while let Some(State {
-        opened,
-        curr,
-        elapsed,
-        relieved,
-    }) = q.pop_front()
-    {
-        // If all flowing valves are already open, wait until the end
-        if opened.len() == flowing.len() || elapsed >= PART1_TIME_LIMIT {
-            let relieved_at_end =
-                wait_until_times_up(PART1_TIME_LIMIT, elapsed, relieved, &opened, &map);
-            max_relieved = max_relieved.max(relieved_at_end);
-            continue;
+impl<'a> ValveSearch<'a, '_> {
+    fn start(&self) -> State<'a> {
+        State {
+            curr: START,
+            opened: BitSet64::new(),
+            elapsed: 0,
+            relieved: 0,
+            elephant: false,
         }
-        // for every unopened valve, run the simulation/traverse graph
-        let unopened = flowing.iter().filter(|name| !opened.contains(*name));
+    }
+}
 
+impl<'a> Problem for ValveSearch<'a, '_> {
+    type State = State<'a>;
+    type Key = (BitSet64, &'a str, u32, bool);
+
+    fn successors(&self, state: &State<'a>) -> Vec<State<'a>> {
+        // for every unopened valve, run the simulation/traverse graph
+        let unopened = (0..self.flowing.len()).filter(|&i| !state.opened.contains(i));
+        let mut next = vec![];
         for dest in unopened {
             // how long would moving to dest take? +1 to open the valve
-            let cost = dist_map[&(curr, *dest)] + 1;
-            let new_elapsed = elapsed + cost;
-            // if openeing the dest valve would exceed the time limit, wait until the end
-            if new_elapsed >= PART1_TIME_LIMIT {
-                let relieved_at_end =
-                    wait_until_times_up(PART1_TIME_LIMIT, elapsed, relieved, &opened, &map);
-                max_relieved = max_relieved.max(relieved_at_end);
+            let dest_name = *self.flowing.key(dest);
+            let cost = self.dist_map.weight(&state.curr, &dest_name).unwrap() + 1;
+            let new_elapsed = state.elapsed + cost;
+            // if opening the dest valve would exceed the time limit, waiting is all that's left
+            if new_elapsed >= self.time_limit {
                 continue;
             }
-
-            // relieve pressure of opened valves while we move to dest and open the valve there
-            let relieved_per_minute: u32 = opened.iter().map(|name| &map[name].flow).sum();
-            let new_relieved = relieved + relieved_per_minute * cost;
-            // add opened valve to opened valve HashSet
-            let mut new_opened = opened.clone();
-            new_opened.insert(dest);
-
-            if seen.insert((new_opened.clone(), new_elapsed, new_relieved)) {
-                q.push_back(State {
-                    opened: new_opened,
-                    curr: dest,
-                    elapsed: new_elapsed,
-                    relieved: new_relieved,
-                });
-            }
+            next.push(State {
+                // add opened valve to opened valve set
+                opened: state.opened.with(dest),
+                curr: dest_name,
+                elapsed: new_elapsed,
+                relieved: state.relieved + self.flows[dest] * (self.time_limit - new_elapsed),
+                ..*state
+            });
+        }
+        if self.with_elephant && !state.elephant {
+            next.push(State {
+                curr: START,
+                elapsed: 0,
+                elephant: true,
+                ..*state
+            });
         }
+        next
     }
-    max_relieved
-}
-
-fn part2(input: &str) -> u32 {
-    let map = parse_input(input).finish().unwrap().1;
-    let dist_map = min_distances(&map); // key: (from, to), value: move_cost
-    let flowing: HashSet<_> = map
-        .iter()
-        .filter(|(_, valve)| valve.flow > 0)
-        .map(|(&name, _)| name)
-        .collect();
-
-    // key: opened, val: relieved_at_end
-    let mut max_relieved_states: HashMap<BTreeSet<&str>, u32> = HashMap::new();
-
-    let mut q = VecDeque::new();
-    q.push_back(State {
-        curr: "AA",
-        opened: BTreeSet::new(),
-        elapsed: 0,
-        relieved: 0,
-    });
 
-    while let Some(State {
-        opened,
-        curr,
-        elapsed,
-        relieved,
-    }) = q.pop_front()
-    {
-        let relieved_at_end =
-            wait_until_times_up(PART2_TIME_LIMIT, elapsed, relieved, &opened, &map);
-        // record state. only update state if it beats the `relieved_at_end` value
-        max_relieved_states
-            .entry(opened.clone())
-            .and_modify(|val| *val = relieved_at_end.max(*val))
-            .or_insert(relieved_at_end);
+    fn value(&self, state: &State<'a>) -> i64 {
+        state.relieved as i64
+    }
 
-        // if all flowing valves are opened or time limit is reached, skip
-        if opened.len() == flowing.len() || elapsed >= PART2_TIME_LIMIT {
-            continue;
-        }
-        // for every unopened valve, run simulation
-        let unopened = flowing.iter().filter(|name| !opened.contains(*name));
+    /// Pretend every unopened valve could be opened 2 minutes from now (1 to move, 1 to open),
+    /// or 2 minutes into the elephant's turn if it hasn't had one yet
+    fn upper_bound(&self, state: &State<'a>) -> i64 {
+        let elapsed = match self.with_elephant && !state.elephant {
+            true => 0,
+            false => state.elapsed,
+        };
+        let time_left = (self.time_limit - elapsed).saturating_sub(2);
+        let unopened_flow: u32 = (0..self.flowing.len())
+            .filter(|&i| !state.opened.contains(i))
+            .map(|i| self.flows[i])
+            .sum();
+        self.value(state) + (unopened_flow * time_left) as i64
+    }
 
-        for dest in unopened {
-            // how long would moving to dest take? +1 to open the valve
-            let cost = dist_map[&(curr, *dest)] + 1;
-            let new_elapsed = elapsed + cost;
-            // if openeing the dest valve would exceed the time limit, wait until the end
-            if new_elapsed >= PART2_TIME_LIMIT {
-                continue;
-            }
+    fn dominance(&self, state: &State<'a>) -> Option<(Self::Key, i64)> {
+        Some((
+            (state.opened, state.curr, state.elapsed, state.elephant),
+            state.relieved as i64,
+        ))
+    }
+}
 
-            // relieve pressure of opened valves while we move to dest and open the valve there
-            let relieved_per_minute: u32 = opened.iter().map(|name| &map[name].flow).sum();
-            let new_relieved = relieved + relieved_per_minute * cost;
+/// Most pressure that can be relieved within `time_limit`, alone or with the elephant's help
+fn most_relieved(input: &str, time_limit: u32, with_elephant: bool) -> u32 {
+    let map = parse_input(input).unwrap_or_else(|e| panic!("{e}"));
+    let dist_map = min_distances(&map, START);
+    let (flowing, flows) = flowing_valves(&map);
+
+    let search = ValveSearch {
+        dist_map: &dist_map,
+        flowing: &flowing,
+        flows: &flows,
+        time_limit,
+        with_elephant,
+    };
+    maximize(&search, search.start(), None).value as u32
+}
 
-            // add opened valve to opened valve HashSet
-            let mut new_opened = opened.clone();
-            new_opened.insert(dest);
+pub fn part1(input: &str) -> u32 {
+    most_relieved(input, PART1_TIME_LIMIT, false)
+}
 
-            q.push_back(State {
-                opened: new_opened,
-                curr: dest,
-                elapsed: new_elapsed,
-                relieved: new_relieved,
-            });
-        }
-    }
+fn part2(input: &str) -> u32 {
+    most_relieved(input, PART2_TIME_LIMIT, true)
+}
 
-    max_relieved_states
-        .iter()
-        .tuple_combinations()
-        .filter(|(human, elephant)| human.0.is_disjoint(elephant.0))
-        .map(|(human, elephant)| human.1 + elephant.1)
-        .max()
-        .unwrap()
+fn parse_valve(line: &str) -> IResult<&str, (&str, Valve<'_>)> {
+    let (rest, (_, id, _, flow, _, _, adjacent)) = tuple((
+        tag("Valve "),
+        alpha1,
+        tag(" has flow rate="),
+        unsigned,
+        alt((tag("; tunnels lead to "), tag("; tunnel leads to "))),
+        alt((tag("valves "), tag("valve "))),
+        comma_list(alpha1),
+    ))(line)?;
+    let valve = Valve {
+        flow,
+        adjacent: adjacent.into_iter().collect(),
+    };
+    Ok((rest, (id, valve)))
 }
 
-fn parse_input(input: &str) -> IResult<&str, HashMap<&str, Valve>> {
-    let mut valves = HashMap::new();
-    for line in input.lines() {
-        let (_, (_, id, _, flow, _, _, adjacent)) = tuple((
-            tag("Valve "),
-            alpha1,
-            tag(" has flow rate="),
-            cc::u32,
-            alt((tag("; tunnels lead to "), tag("; tunnel leads to "))),
-            alt((tag("valves "), tag("valve "))),
-            separated_list0(tag(", "), alpha1),
-        ))(line)?;
-        valves.insert(
-            id,
-            Valve {
-                flow,
-                adjacent: adjacent.into_iter().collect(),
-            },
-        );
-    }
-    Ok((input, valves))
+fn parse_input(input: &str) -> Result<HashMap<&str, Valve<'_>>, ParseError> {
+    Ok(parse_lines(input, parse_valve)?.into_iter().collect())
 }
 
 pub fn run() {
     println!("\n=== Day 16 ===");
-    let input = include_str!("input.txt");
-    // for (k, v) in parse_input(input).finish().unwrap().1.iter() {
+    let input = input::prepare(16, include_str!("input.txt"));
+    // for (k, v) in parse_input(input).unwrap().iter() {
     //     println!("{k}: {v:?}");
     // }
-    println!("Part 1: {}", part1(input));
-    println!("Part 2: {}", part2(input));
+    println!("Part 1: {}", part1(&input));
+    println!("Part 2: {}", part2(&input));
+}
+
+#[test]
+fn test_sample() {
+    let input = include_str!("sample_input.txt");
+    assert_eq!(part1(input), 1651);
+    assert_eq!(part2(input), 1707);
 }
@@ -0,0 +1,171 @@
+/// The chamber simulated rock by rock, with every row kept as a bitmask (bit `x` is column `x`),
+/// so overhangs and rocks sliding in under them come out right.
+///
+/// A trillion rocks can't be dropped one at a time, but which rock and which jet come next
+/// repeat, and so does the part of the tower a falling rock can still get to. `tower_height` runs
+/// the simulation through `cycle::extrapolate` until that happens and works the rest out from
+/// there.
+use super::Direction;
+use crate::shared::cycle;
+
+const WIDTH: usize = 7;
+
+/// The rocks in the order they fall, bottom row first, at their leftmost position
+const ROCKS: [&[u8]; 5] = [
+    &[0b1111],
+    &[0b010, 0b111, 0b010],
+    &[0b111, 0b100, 0b100],
+    &[0b1, 0b1, 0b1, 0b1],
+    &[0b11, 0b11],
+];
+
+#[derive(Debug, Clone)]
+pub(super) struct Chamber<'j> {
+    jets: &'j [Direction],
+    next_jet: usize,
+    rocks: usize,
+    rows: Vec<u8>,
+}
+
+impl<'j> Chamber<'j> {
+    pub(super) fn new(jets: &'j [Direction]) -> Self {
+        Self {
+            jets,
+            next_jet: 0,
+            rocks: 0,
+            rows: vec![],
+        }
+    }
+
+    pub(super) fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Can `rock` be at column `x`, row `y` without hitting a wall or anything that's settled?
+    fn fits(&self, rock: &[u8], x: usize, y: usize) -> bool {
+        rock.iter().enumerate().all(|(dy, &row)| {
+            let row = u16::from(row) << x;
+            row < 1 << WIDTH
+                && self
+                    .rows
+                    .get(y + dy)
+                    .is_none_or(|&r| u16::from(r) & row == 0)
+        })
+    }
+
+    /// Drop the next rock until it comes to rest
+    pub(super) fn drop_rock(&mut self) {
+        let rock = ROCKS[self.rocks % ROCKS.len()];
+        let (mut x, mut y) = (2_usize, self.rows.len() + 3);
+        loop {
+            let pushed = match self.jets[self.next_jet] {
+                Direction::Left => x.checked_sub(1),
+                Direction::Right => Some(x + 1),
+            };
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+            if let Some(pushed) = pushed.filter(|&px| self.fits(rock, px, y)) {
+                x = pushed;
+            }
+            if y == 0 || !self.fits(rock, x, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+        for (dy, row) in rock.iter().enumerate() {
+            if y + dy == self.rows.len() {
+                self.rows.push(0);
+            }
+            self.rows[y + dy] |= row << x;
+        }
+        self.rocks += 1;
+    }
+
+    /// Everything the next rocks can depend on: the empty cells a falling rock could get to,
+    /// row by row down from just above the tower.
+    ///
+    /// Rocks only move down and sideways, so every cell of one stays inside what a single cell
+    /// can reach that way, and anything it can bump into borders on that. The rest of the tower
+    /// is buried for good.
+    fn fingerprint(&self) -> (usize, usize, Vec<u8>) {
+        let top = self.rows.len();
+        let empty = |depth: usize, x: usize| {
+            depth <= top && x < WIDTH && self.rows.get(top - depth).is_none_or(|r| r >> x & 1 == 0)
+        };
+        let mut reached = vec![0_u8];
+        let mut stack = (0..WIDTH).map(|x| (0, x)).collect::<Vec<_>>();
+        while let Some((depth, x)) = stack.pop() {
+            if reached[depth] >> x & 1 == 1 {
+                continue;
+            }
+            reached[depth] |= 1 << x;
+            let next = [(depth, x.wrapping_sub(1)), (depth, x + 1), (depth + 1, x)];
+            for (depth, x) in next.into_iter().filter(|&(d, x)| empty(d, x)) {
+                if depth == reached.len() {
+                    reached.push(0);
+                }
+                stack.push((depth, x));
+            }
+        }
+        (self.rocks % ROCKS.len(), self.next_jet, reached)
+    }
+}
+
+/// Height of the tower after `rocks` rocks have fallen
+pub(super) fn tower_height(jets: &[Direction], rocks: usize) -> usize {
+    // which rock and jet come next repeat within `ROCKS.len() * jets.len()` rocks, give the top
+    // of the tower a few rounds of that to settle down too
+    let max_rocks = 4 * ROCKS.len() * jets.len();
+    cycle::extrapolate(
+        Chamber::new(jets),
+        Chamber::drop_rock,
+        Chamber::fingerprint,
+        |chamber| chamber.height() as i64,
+        rocks,
+        max_rocks,
+    )
+    .expect("the tower never started repeating") as usize
+}
+
+#[test]
+fn test_tower_height() {
+    let jets = super::parse(include_str!("sample_input.txt"));
+    assert_eq!(tower_height(&jets, 2022), 3068);
+    assert_eq!(tower_height(&jets, 1_000_000_000_000), 1514285714288);
+}
+
+#[test]
+fn test_extrapolated_height_matches_simulation() {
+    use crate::shared::rng::Rng;
+
+    for seed in 0..5 {
+        let input = super::generate::generate(&mut Rng::new(seed), 1);
+        let jets = super::parse(&input);
+        let mut chamber = Chamber::new(&jets);
+        for rocks in 1..=15_000 {
+            chamber.drop_rock();
+            if rocks % 2500 == 0 {
+                assert_eq!(tower_height(&jets, rocks), chamber.height(), "seed {seed}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_fingerprint_follows_deep_shafts() {
+    let jets = [Direction::Left];
+    // a one wide shaft down the right hand wall of a 60 row tower, blocked 50 rows down in one
+    let shaft = |blocked: Option<usize>| Chamber {
+        rows: (0..60)
+            .map(|y| match Some(60 - y) == blocked {
+                true => 0b1111111,
+                false => 0b0111111,
+            })
+            .collect(),
+        ..Chamber::new(&jets)
+    };
+    let (open, blocked) = (shaft(None), shaft(Some(50)));
+    assert_eq!(open.rows[20..], blocked.rows[20..]);
+    assert_ne!(open.fingerprint(), blocked.fingerprint());
+    // all the way down to the floor: the row above the tower and every row in it
+    assert_eq!(open.fingerprint().2.len(), 61);
+}
@@ -0,0 +1,15 @@
+/// A random line of jet directions
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = (0..size * 1000)
+        .map(|_| *rng.choose(&['<', '>']))
+        .collect::<String>();
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    assert!(!super::parse(input).is_empty());
+}
@@ -0,0 +1,24 @@
+/// A random lump of lava droplets. The cube is filled about halfway, which leaves plenty of air
+/// pockets trapped inside for part 2.
+use std::{collections::HashSet, fmt::Write};
+
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let count = (size * 280).max(1);
+    let side = ((count * 2) as f64).cbrt().ceil() as u32;
+    let mut seen = HashSet::new();
+    let mut out = String::new();
+    while seen.len() < count {
+        let cube = [(); 3].map(|_| rng.range(0..=side - 1));
+        if seen.insert(cube) {
+            writeln!(out, "{},{},{}", cube[0], cube[1], cube[2]).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    assert!(!super::parse(input.to_string()).is_empty());
+}
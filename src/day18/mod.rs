@@ -1,70 +1,13 @@
-use std::{collections::HashSet, ops::Add};
+use std::collections::HashSet;
 
-#[derive(Clone, Copy, Hash, PartialEq, Eq)]
-struct Vertex3D {
-    x: isize,
-    y: isize,
-    z: isize,
-}
-
-impl Add<(isize, isize, isize)> for Vertex3D {
-    type Output = Vertex3D;
-    fn add(self, rhs: (isize, isize, isize)) -> Self::Output {
-        Vertex3D {
-            x: self.x + rhs.0,
-            y: self.y + rhs.1,
-            z: self.z + rhs.2,
-        }
-    }
-}
-
-impl From<(isize, isize, isize)> for Vertex3D {
-    fn from(value: (isize, isize, isize)) -> Self {
-        Self {
-            x: value.0,
-            y: value.1,
-            z: value.2,
-        }
-    }
-}
+pub(crate) mod generate;
 
-impl Default for Vertex3D {
-    fn default() -> Self {
-        Self { x: 0, y: 0, z: 0 }
-    }
-}
-
-impl std::fmt::Debug for Vertex3D {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({:>2}, {:>2}, {:>2})", self.x, self.y, self.z)
-    }
-}
-
-impl Vertex3D {
-    fn neighbors(&self) -> HashSet<Vertex3D> {
-        [
-            (-1, 0, 0), // left
-            (0, 1, 0),  // up
-            (1, 0, 0),  // right
-            (0, -1, 0), // down
-            (0, 0, 1),  // above
-            (0, 0, -1), // below
-        ]
-        .iter()
-        .map(|pt| *self + *pt)
-        .collect()
-    }
-
-    fn in_bounds(&self, bounds: &[Self; 2]) -> bool {
-        let [mins, maxs] = bounds;
-        self.x >= mins.x - 1
-            && self.x <= maxs.x + 1
-            && self.y >= mins.y - 1
-            && self.y <= maxs.y + 1
-            && self.z >= mins.z - 1
-            && self.z <= maxs.z + 1
-    }
-}
+#[cfg(test)]
+use crate::shared::voxel::Bounds3;
+use crate::shared::{
+    flood::Connectivity,
+    voxel::{Grid3, Vec3 as Vertex3D},
+};
 
 fn parse(input: String) -> HashSet<Vertex3D> {
     input
@@ -83,57 +26,21 @@ fn parse(input: String) -> HashSet<Vertex3D> {
         .collect()
 }
 
-fn grid_bounds(verteces: &HashSet<Vertex3D>) -> [Vertex3D; 2] {
-    verteces.iter().fold(
-        [Vertex3D::default(), Vertex3D::default()],
-        |[mut mins, mut maxs], cube| {
-            mins.x = mins.x.min(cube.x);
-            mins.y = mins.y.min(cube.y);
-            mins.z = mins.z.min(cube.z);
-            maxs.x = maxs.x.max(cube.x);
-            maxs.y = maxs.y.max(cube.y);
-            maxs.z = maxs.z.max(cube.z);
-            [mins, maxs]
-        },
-    )
-}
-
+/// Every air cell connected to the outside of the droplet, within a 1-cell border around it
 fn flood_fill_exposed_cubes(verteces: &HashSet<Vertex3D>) -> HashSet<Vertex3D> {
-    let bounds = grid_bounds(verteces);
-    let mut exposed = HashSet::new();
-
-    let start = Vertex3D::default();
-    let mut stack = Vec::new();
-    let mut seen = HashSet::new();
-
-    stack.push(start);
-    seen.insert(start);
-
-    while let Some(vertex) = stack.pop() {
-        for vx in vertex.neighbors() {
-            if verteces.contains(&vx) || !vx.in_bounds(&bounds) {
-                continue;
-            }
-            // `insert` returns true if was not previously in set, false if already in set.
-            if seen.insert(vx) {
-                stack.push(vx);
-                exposed.insert(vx);
-            }
-        }
-    }
-    exposed
+    let grid = Grid3::from_points(verteces, true, false, 1);
+    // the padded corner can't be part of the droplet
+    grid.flood_fill([grid.bounds().min], Connectivity::Orthogonal, |lava| !lava)
 }
 
 /// for each vertex (center of a cube in the 3D grid), check how many of its neighbors
 /// are present in the set of other verteces. If there is a neighbor present, that is
 /// an adjoining cube, meaning that face is not exposed as part of the surface area.
 ///
-/// The set difference is the number of faces exposed.
+/// Every face without a neighboring cube is exposed.
 fn part_1(input: String) -> usize {
     let verteces = parse(input);
-    verteces.iter().fold(0, |acc, vx| {
-        acc + vx.neighbors().difference(&verteces).count()
-    })
+    Grid3::from_points(&verteces, true, false, 0).surface_area(|lava| *lava)
 }
 
 /// Only count the neighbors for cubes on the outside of the lava droplet
@@ -142,13 +49,13 @@ fn part_2(input: String) -> usize {
     let exposed = flood_fill_exposed_cubes(&verteces);
     verteces
         .iter()
-        .flat_map(|vx| vx.neighbors())
+        .flat_map(|vx| vx.neighbors6())
         .filter(|vx| exposed.contains(vx))
         .count()
 }
 
 pub fn run() {
-    let input = std::fs::read_to_string("src/day18/input.txt").expect("error reading input file");
+    let input = crate::shared::input::load(18);
     println!("\n=== Day 18 ===");
     println!("Part 1: {}", part_1(input.clone()));
     println!("Part 2: {}", part_2(input))
@@ -195,7 +102,7 @@ fn test_neighbors() {
         Vertex3D::from((0, 0, 1)),
         Vertex3D::from((0, 0, -1)),
     ]);
-    assert!(test_vertex.neighbors().is_superset(&test_set))
+    assert_eq!(HashSet::from(test_vertex.neighbors6()), test_set)
 }
 
 #[test]
@@ -206,10 +113,13 @@ fn test_part_1() {
 #[test]
 fn test_grid_bounds() {
     let verteces = parse(SAMPLE_INPUT.to_string());
-    let bounds = grid_bounds(&verteces);
+    let bounds = Bounds3::from_points(&verteces).unwrap();
     assert_eq!(
         bounds,
-        [Vertex3D { x: 0, y: 0, z: 0 }, Vertex3D { x: 3, y: 3, z: 6 }]
+        Bounds3 {
+            min: Vertex3D { x: 1, y: 1, z: 1 },
+            max: Vertex3D { x: 3, y: 3, z: 6 }
+        }
     )
 }
 
@@ -217,9 +127,9 @@ fn test_grid_bounds() {
 fn test_in_bounds() {
     let in_bounds_vertex = Vertex3D::from((2, 2, 2));
     let oob_vertex = Vertex3D::from((2, 3, 4)); // +/- 1 in bounds for neighbors' sake
-    let bounds = [Vertex3D::default(), Vertex3D::from((2, 2, 2))];
-    assert!(in_bounds_vertex.in_bounds(&bounds));
-    assert!(!oob_vertex.in_bounds(&bounds));
+    let bounds = Bounds3::new(Vertex3D::default(), Vertex3D::from((2, 2, 2))).expand(1);
+    assert!(bounds.contains(in_bounds_vertex));
+    assert!(!bounds.contains(oob_vertex));
 }
 
 #[test]
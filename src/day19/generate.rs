@@ -0,0 +1,29 @@
+/// Random robot blueprints, with costs in the same ranges as the real puzzle. There are always
+/// at least 3 since part 2 looks at the first 3.
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for id in 1..=(size * 3).max(3) {
+        writeln!(
+            out,
+            "Blueprint {id}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+            Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.",
+            rng.range(2..=4),
+            rng.range(2..=4),
+            rng.range(2..=4),
+            rng.range(4..=20),
+            rng.range(2..=4),
+            rng.range(7..=20),
+        )
+        .unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::parse(input).unwrap();
+}
@@ -1,10 +1,18 @@
-use nom::{bytes::complete::tag, character::complete as cc, sequence::tuple, Finish, IResult};
-use std::collections::HashMap;
+use nom::{bytes::complete::tag, sequence::tuple, IResult};
 
-const COST_MASK: u16 = 0b0000_0000_1111_1111;
+use crate::shared::{
+    input,
+    parse::{parse_lines, unsigned, ParseError},
+    search::optimize::{maximize, Problem},
+};
+
+pub(crate) mod generate;
+
+const PART1_TIME_LIMIT: u16 = 24;
+const PART2_TIME_LIMIT: u16 = 32;
 
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
-/// Robot types
+/// Robot types, also the index of the resource each one collects
 enum Robot {
     Ore,
     Clay,
@@ -18,189 +26,187 @@ impl Robot {
     }
 }
 
+/// each cost is [ore, clay, obsidian, geode], indexed by the robot being built
+#[derive(Debug)]
+struct Blueprint {
+    id: u16,
+    costs: [[u16; 4]; 4],
+    /// No point in building more robots of a type than the most of its resource we can spend
+    /// in one minute (only one robot gets built per minute). Geode robots are never capped.
+    max_robots: [u16; 4],
+}
+
+impl Blueprint {
+    fn new(id: u16, costs: [[u16; 4]; 4]) -> Self {
+        let mut max_robots = [u16::MAX; 4];
+        for (i, max) in max_robots.iter_mut().take(3).enumerate() {
+            *max = costs.iter().map(|cost| cost[i]).max().unwrap();
+        }
+        Self {
+            id,
+            costs,
+            max_robots,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
 struct Factory {
-    clay: u16,
-    ore: u16,
-    obsidian: u16,
-    geodes: u16,
-    robot_costs: HashMap<Robot, u16>,
-    robots: HashMap<Robot, u16>,
+    /// [ore, clay, obsidian, geode]
+    inventory: [u16; 4],
+    /// number of each robot type
+    robots: [u16; 4],
+    elapsed: u16,
 }
 
-impl Factory {
-    fn new(robot_costs: HashMap<Robot, u16>) -> Self {
+impl Default for Factory {
+    fn default() -> Self {
         Self {
-            clay: 0,
-            ore: 0,
-            obsidian: 0,
-            geodes: 0,
-            robot_costs,
-            robots: HashMap::from_iter(vec![
-                (Robot::Ore, 1),
-                (Robot::Clay, 0),
-                (Robot::Obsidian, 0),
-                (Robot::Geode, 0),
-            ]),
+            inventory: [0; 4],
+            robots: [1, 0, 0, 0],
+            elapsed: 0,
         }
     }
+}
+
+/// Most geodes a blueprint can crack before time runs out
+struct GeodeSearch<'a> {
+    blueprint: &'a Blueprint,
+    time_limit: u16,
+}
 
-    fn get_costs(num: u16) -> (u16, u16) {
-        let ore_cost = num & COST_MASK;
-        let other_cost = (num >> 8) & COST_MASK;
-        (ore_cost, other_cost)
+impl GeodeSearch<'_> {
+    /// Minutes to wait until `robot` is affordable, `None` if nothing collects a needed resource
+    fn wait_time(&self, factory: &Factory, robot: Robot) -> Option<u16> {
+        let costs = &self.blueprint.costs[robot as usize];
+        (0..3)
+            .map(|i| match costs[i] {
+                cost if cost <= factory.inventory[i] => Some(0),
+                _ if factory.robots[i] == 0 => None,
+                cost => Some((cost - factory.inventory[i]).div_ceil(factory.robots[i])),
+            })
+            .try_fold(0, |acc, wait| Some(acc.max(wait?)))
     }
+}
+
+impl Problem for GeodeSearch<'_> {
+    type State = Factory;
+    type Key = Factory;
 
-    fn build_robot(&mut self) -> Option<Robot> {
-        for robot_type in Robot::iter() {
-            let (ore, other) = Factory::get_costs(*(self.robot_costs.get(&robot_type).unwrap()));
-            match robot_type {
-                Robot::Geode => {
-                    // println!(
-                    //     "{robot_type:?} -> Need ({ore}, {other}). Have ({}, {})",
-                    //     self.ore, self.obsidian
-                    // );
-                    if self.ore > 0
-                        && self.obsidian > 0
-                        && self.ore >= ore
-                        && self.obsidian >= other
-                    {
-                        self.ore -= ore;
-                        self.obsidian -= other;
-                        return Some(Robot::Geode);
-                    }
-                }
-                Robot::Obsidian => {
-                    // println!(
-                    //     "{robot_type:?} -> Need ({ore}, {other}). Have ({}, {})",
-                    //     self.ore, self.clay
-                    // );
-                    if self.robots[&Robot::Obsidian] > 2 {
-                        if !(self.robots[&Robot::Geode] > 0) {
-                            continue;
-                        }
-                    }
-                    if self.ore > 0 && self.clay > 0 && self.ore >= ore && self.clay >= other {
-                        self.ore -= ore;
-                        self.clay -= other;
-                        return Some(Robot::Obsidian);
-                    }
-                }
-                Robot::Clay => {
-                    // println!(
-                    //     "{robot_type:?} -> Need ({ore}, {other}). Have ({}, 0)",
-                    //     self.ore
-                    // );
-                    if self.robots[&Robot::Clay] > 2 {
-                        if !(self.robots[&Robot::Geode] > 2) && !(self.robots[&Robot::Obsidian] > 1)
-                        {
-                            continue;
-                        }
-                    }
-                    if self.ore >= ore && ore > 0 {
-                        self.ore -= ore;
-                        return Some(Robot::Clay);
-                    }
-                }
-                Robot::Ore => {
-                    // println!(
-                    //     "{robot_type:?} -> Need ({ore}, {other}). Have ({}, 0)",
-                    //     self.ore
-                    // );
-                    if (self.robots[&Robot::Clay] > 3)
-                        && (self.robots[&Robot::Obsidian] > 2)
-                        && (self.robots[&Robot::Geode] > 1)
-                    {
-                        if self.ore >= ore && ore > 0 {
-                            self.ore -= ore;
-                            return Some(Robot::Ore);
-                        }
-                    }
-                }
+    /// Instead of simulating every minute, jump straight to building each robot type we can
+    /// eventually afford
+    fn successors(&self, factory: &Factory) -> Vec<Factory> {
+        let mut next = vec![];
+        for &robot in Robot::iter() {
+            let i = robot as usize;
+            if factory.robots[i] >= self.blueprint.max_robots[i] {
+                continue;
             }
-        }
-        None
-    }
-    fn cycle(&mut self, minutes: usize) {
-        for _ in 0..minutes {
-            // println!("\nMinute {i}");
-            let build_robot = self.build_robot();
-            for (robot, count) in self.robots.iter() {
-                if *count > 0 {
-                    match robot {
-                        Robot::Ore => self.ore += count,
-                        Robot::Clay => self.clay += count,
-                        Robot::Obsidian => self.obsidian += count,
-                        Robot::Geode => self.geodes += count,
-                    }
-                }
+            let Some(wait) = self.wait_time(factory, robot) else {
+                continue;
+            };
+            // +1 to build it, and it has to finish before time's up to be any use
+            let elapsed = factory.elapsed + wait + 1;
+            if elapsed >= self.time_limit {
+                continue;
             }
-            if let Some(robot) = build_robot {
-                self.robots
-                    .entry(robot)
-                    .and_modify(|e| *e += 1)
-                    .or_insert(1);
+            let mut new = factory.clone();
+            for (j, amount) in new.inventory.iter_mut().enumerate() {
+                // add before paying, the new production alone may not cover the cost
+                *amount = *amount + factory.robots[j] * (wait + 1) - self.blueprint.costs[i][j];
             }
+            new.robots[i] += 1;
+            new.elapsed = elapsed;
+            next.push(new);
         }
+        next
     }
-}
 
-impl std::fmt::Debug for Factory {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Factory:\n  clay:     {}\n  ore:      {}\n  obsidian: {}\n  geodes:   {}\n  costs:\n",
-            self.clay, self.ore, self.obsidian, self.geodes
-        )?;
-        for (k, v) in self.robot_costs.iter() {
-            writeln!(f, "    {k:?} -> {:?}", Factory::get_costs(*v))?;
-        }
-        writeln!(f, "  robots: {:?}", self.robots)
+    /// Geodes at the end if we stop building now
+    fn value(&self, factory: &Factory) -> i64 {
+        let time_left = self.time_limit - factory.elapsed;
+        (factory.inventory[3] + factory.robots[3] * time_left) as i64
+    }
+
+    /// ... plus a new geode robot every remaining minute
+    fn upper_bound(&self, factory: &Factory) -> i64 {
+        let time_left = (self.time_limit - factory.elapsed) as i64;
+        self.value(factory) + time_left * (time_left - 1) / 2
     }
-}
 
-fn parse(input: &str) -> IResult<&str, Vec<Factory>> {
-    let mut factories = vec![];
-    for line in input.lines() {
-        let mut robot_costs = HashMap::new();
-        let (_, (_, _, _, ore, _, clay, _, obsidian1, _, obsidian2, _, geode1, _, geode2, _)) =
-            tuple((
-                tag("Blueprint "),
-                cc::u16,
-                tag(": Each ore robot costs "),
-                cc::u16,
-                tag(" ore. Each clay robot costs "),
-                cc::u16,
-                tag(" ore. Each obsidian robot costs "),
-                cc::u16,
-                tag(" ore and "),
-                cc::u16,
-                tag(" clay. Each geode robot costs "),
-                cc::u16,
-                tag(" ore and "),
-                cc::u16,
-                tag(" obsidian."),
-            ))(line)?;
-        robot_costs.insert(Robot::Ore, ore);
-        robot_costs.insert(Robot::Clay, clay);
-        let obsidian = (obsidian2 << 8) | obsidian1;
-        robot_costs.insert(Robot::Obsidian, obsidian);
-        let geode = (geode2 << 8) | geode1;
-        robot_costs.insert(Robot::Geode, geode);
-        factories.push(Factory::new(robot_costs));
+    fn dominance(&self, factory: &Factory) -> Option<(Factory, i64)> {
+        Some((factory.clone(), 0))
     }
-    Ok((input, factories))
+}
+
+fn max_geodes(blueprint: &Blueprint, time_limit: u16) -> u16 {
+    let search = GeodeSearch {
+        blueprint,
+        time_limit,
+    };
+    let outcome = maximize(&search, Factory::default(), None);
+    outcome.value as u16
+}
+
+fn part1(blueprints: &[Blueprint]) -> usize {
+    blueprints
+        .iter()
+        .map(|bp| bp.id as usize * max_geodes(bp, PART1_TIME_LIMIT) as usize)
+        .sum()
+}
+
+fn part2(blueprints: &[Blueprint]) -> usize {
+    blueprints
+        .iter()
+        .take(3)
+        .map(|bp| max_geodes(bp, PART2_TIME_LIMIT) as usize)
+        .product()
+}
+
+fn parse_blueprint(line: &str) -> IResult<&str, Blueprint> {
+    let (rest, (_, id, _, ore, _, clay, _, obsidian1, _, obsidian2, _, geode1, _, geode2, _)) =
+        tuple((
+            tag("Blueprint "),
+            unsigned,
+            tag(": Each ore robot costs "),
+            unsigned,
+            tag(" ore. Each clay robot costs "),
+            unsigned,
+            tag(" ore. Each obsidian robot costs "),
+            unsigned,
+            tag(" ore and "),
+            unsigned,
+            tag(" clay. Each geode robot costs "),
+            unsigned,
+            tag(" ore and "),
+            unsigned,
+            tag(" obsidian."),
+        ))(line)?;
+    let blueprint = Blueprint::new(
+        id,
+        [
+            [ore, 0, 0, 0],
+            [clay, 0, 0, 0],
+            [obsidian1, obsidian2, 0, 0],
+            [geode1, 0, geode2, 0],
+        ],
+    );
+    Ok((rest, blueprint))
+}
+
+fn parse(input: &str) -> Result<Vec<Blueprint>, ParseError> {
+    parse_lines(input, parse_blueprint)
 }
 
 pub fn run() {
     println!("\n=== Day 19 ===");
-    // println!("{SAMPLE_INPUT}");
-    let mut factories = parse(SAMPLE_INPUT).finish().unwrap().1;
-    factories[0].cycle(24);
-    println!("Part 1: TODO!!");
-    println!("Part 2: TODO!!");
-    // println!("{:?}", factories[0])
+    let blueprints =
+        parse(&input::prepare(19, include_str!("input.txt"))).unwrap_or_else(|e| panic!("{e}"));
+    println!("Part 1: {}", part1(&blueprints));
+    println!("Part 2: {}", part2(&blueprints));
 }
 
+#[cfg(test)]
 const SAMPLE_INPUT: &str = "\
 Blueprint 1: \
  Each ore robot costs 4 ore. \
@@ -212,3 +218,14 @@ Blueprint 2: \
  Each clay robot costs 3 ore. \
  Each obsidian robot costs 3 ore and 8 clay. \
  Each geode robot costs 3 ore and 12 obsidian.";
+
+#[test]
+fn test_sample() {
+    let blueprints = parse(SAMPLE_INPUT).unwrap();
+    assert_eq!(max_geodes(&blueprints[0], PART1_TIME_LIMIT), 9);
+    assert_eq!(max_geodes(&blueprints[1], PART1_TIME_LIMIT), 12);
+    assert_eq!(part1(&blueprints), 33);
+    assert_eq!(max_geodes(&blueprints[0], PART2_TIME_LIMIT), 56);
+    assert_eq!(max_geodes(&blueprints[1], PART2_TIME_LIMIT), 62);
+    assert_eq!(part2(&blueprints), 56 * 62);
+}
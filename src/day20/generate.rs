@@ -0,0 +1,30 @@
+/// A random encrypted file: the numbers can repeat, but there's exactly one 0
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let count = (size * 500).max(1);
+    let zero_at = rng.index(count);
+    let mut out = String::new();
+    for i in 0..count {
+        let num = match i == zero_at {
+            true => 0,
+            false => loop {
+                let num = rng.range(-10_000..=10_000);
+                if num != 0 {
+                    break num;
+                }
+            },
+        };
+        writeln!(out, "{num}").unwrap();
+    }
+    out
+}
+
+/// Day 20 isn't solved yet, so there's no parser to go through: just check the numbers
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    let numbers = input.lines().map(|l| l.parse::<i64>().unwrap());
+    assert_eq!(numbers.filter(|&n| n == 0).count(), 1);
+}
@@ -1 +1,3 @@
+pub(crate) mod generate;
+
 pub fn run() {}
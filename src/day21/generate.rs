@@ -0,0 +1,177 @@
+/// Random monkey riddles. The chain of monkeys from `humn` up to `root` is built from a secret
+/// answer, so part 2 always has a whole number solution, and part 1, worked out with what `humn`
+/// actually shouts, always comes out to a positive whole number: every division on that chain
+/// divides evenly either way and `humn` is never the divisor. Everything else hangs off the chain as small random
+/// expressions.
+use std::{collections::HashSet, fmt::Write};
+
+use crate::shared::rng::Rng;
+
+/// Don't let the chain's values get anywhere near overflowing
+const MAX_VALUE: i64 = 1_000_000_000_000;
+
+struct Riddle<'r> {
+    rng: &'r mut Rng,
+    names: HashSet<String>,
+    lines: Vec<String>,
+}
+
+impl Riddle<'_> {
+    fn name(&mut self) -> String {
+        loop {
+            let name = (0..4)
+                .map(|_| self.rng.range(b'a'..=b'z') as char)
+                .collect::<String>();
+            if name != "root" && name != "humn" && self.names.insert(name.clone()) {
+                return name;
+            }
+        }
+    }
+
+    fn leaf(&mut self, value: i64) -> String {
+        let name = self.name();
+        self.lines.push(format!("{name}: {value}"));
+        name
+    }
+
+    fn op(&mut self, lhs: &str, op: char, rhs: &str) -> String {
+        let name = self.name();
+        self.lines.push(format!("{name}: {lhs} {op} {rhs}"));
+        name
+    }
+
+    /// A random expression that doesn't involve `humn`, and its value
+    fn expr(&mut self, depth: usize) -> (String, i64) {
+        if depth == 0 || self.rng.chance(0.3) {
+            let value = self.rng.range(1..=20);
+            return (self.leaf(value), value);
+        }
+        let (lhs, a) = self.expr(depth - 1);
+        let (rhs, b) = self.expr(depth - 1);
+        let (op, value) = match self.rng.index(4) {
+            0 if b != 0 && a % b == 0 => ('/', a / b),
+            1 if a.abs() * b.abs() < 10_000 => ('*', a * b),
+            2 => ('-', a - b),
+            _ => ('+', a + b),
+        };
+        (self.op(&lhs, op, &rhs), value)
+    }
+
+    /// The chain of monkeys from `humn` up to one side of root. Returns its top monkey and what
+    /// it comes out to both when humn says `answer` and when it says `shouted`. A division only
+    /// goes in when it's exact for both, so neither part ever has to truncate.
+    fn chain(&mut self, answer: i64, shouted: i64, size: usize) -> (String, i64, i64) {
+        let (mut chain, mut at_answer, mut at_shouted) = ("humn".to_string(), answer, shouted);
+        for _ in 0..(size * 7).max(1) {
+            let depth = self.rng.range(0..=3);
+            let (other, c) = self.expr(depth);
+            let flip = self.rng.chance(0.5);
+            let (lhs, op, rhs, next) = match self.rng.index(4) {
+                0 if c != 0 && at_answer % c == 0 && at_shouted % c == 0 => {
+                    (&chain, '/', &other, (at_answer / c, at_shouted / c))
+                }
+                1 if c != 0
+                    && (at_answer * c).abs() < MAX_VALUE
+                    && (at_shouted * c).abs() < MAX_VALUE =>
+                {
+                    let next = (at_answer * c, at_shouted * c);
+                    match flip {
+                        true => (&other, '*', &chain, next),
+                        false => (&chain, '*', &other, next),
+                    }
+                }
+                2 => match flip {
+                    true => (&other, '-', &chain, (c - at_answer, c - at_shouted)),
+                    false => (&chain, '-', &other, (at_answer - c, at_shouted - c)),
+                },
+                _ => match flip {
+                    true => (&other, '+', &chain, (c + at_answer, c + at_shouted)),
+                    false => (&chain, '+', &other, (at_answer + c, at_shouted + c)),
+                },
+            };
+            let (lhs, rhs) = (lhs.clone(), rhs.clone());
+            chain = self.op(&lhs, op, &rhs);
+            (at_answer, at_shouted) = next;
+        }
+        (chain, at_answer, at_shouted)
+    }
+}
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let answer = rng.range(1..=5000_i64);
+    let shouted = rng.range(1..=5000_i64);
+    let mut riddle = Riddle {
+        rng,
+        names: HashSet::new(),
+        lines: Vec::new(),
+    };
+
+    let root = loop {
+        riddle.names.clear();
+        riddle.lines = vec![format!("humn: {shouted}")];
+        let (chain, at_answer, at_shouted) = riddle.chain(answer, shouted, size);
+
+        // the other side of root has to come out to `at_answer` for part 2, and part 1 should
+        // come out positive with what humn actually shouts
+        let (other, c) = riddle.expr(2);
+        let fix = riddle.leaf(at_answer - c);
+        let other = riddle.op(&other, '+', &fix);
+        let flip = riddle.rng.chance(0.5);
+        if at_shouted + at_answer > 0 {
+            break match flip {
+                true => format!("root: {chain} + {other}"),
+                false => format!("root: {other} + {chain}"),
+            };
+        } else if at_shouted > at_answer {
+            break format!("root: {chain} - {other}");
+        } else if at_answer > at_shouted {
+            break format!("root: {other} - {chain}");
+        }
+    };
+    riddle.lines.push(root);
+
+    let Riddle { rng, mut lines, .. } = riddle;
+    rng.shuffle(&mut lines);
+    let mut out = String::new();
+    for line in lines {
+        writeln!(out, "{line}").unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    assert!(super::parse(input).contains_key("root"));
+}
+
+#[test]
+fn test_generated_part_1_is_exact() {
+    use super::Monkey;
+
+    /// Part 1 without truncation: `None` if any division along the way leaves a remainder
+    fn exact(name: &str, monkeys: &std::collections::HashMap<&str, Monkey>) -> Option<isize> {
+        match &monkeys[name] {
+            Monkey::Num(n) => Some(*n),
+            Monkey::Calculated(op, lhs, rhs) => {
+                let (lhs, rhs) = (exact(lhs, monkeys)?, exact(rhs, monkeys)?);
+                match op {
+                    super::Op::Add => lhs.checked_add(rhs),
+                    super::Op::Sub => lhs.checked_sub(rhs),
+                    super::Op::Mul => lhs.checked_mul(rhs),
+                    super::Op::Div => (rhs != 0 && lhs % rhs == 0).then(|| lhs / rhs),
+                }
+            }
+        }
+    }
+
+    for seed in 0..200 {
+        for size in [1, 3, 5] {
+            let input = generate(&mut Rng::new(seed), size);
+            let root = exact("root", &super::parse(&input));
+            assert!(
+                root.is_some_and(|n| n > 0),
+                "--seed {seed} --size {size} gives root {root:?}"
+            );
+        }
+    }
+}
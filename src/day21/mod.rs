@@ -1,5 +1,9 @@
 use std::collections::HashMap;
 
+use crate::shared::input;
+
+pub(crate) mod generate;
+
 #[derive(Debug)]
 enum Monkey<'a> {
     Num(isize),
@@ -30,8 +34,7 @@ fn calc_monkey(name: &str, monkeys: &HashMap<&str, Monkey>) -> isize {
     }
 }
 
-fn parse<'a>() -> HashMap<&'a str, Monkey<'a>> {
-    let input = include_str!("input.txt");
+fn parse(input: &str) -> HashMap<&str, Monkey<'_>> {
     let mut monkeys = HashMap::new();
     for line in input.trim().lines() {
         let all = line.split_ascii_whitespace().collect::<Vec<&str>>();
@@ -104,13 +107,13 @@ fn calc_human(name: &str, value: isize, monkeys: &HashMap<&str, Monkey>) -> isiz
     }
 }
 
-fn part_1() -> isize {
-    let monkeys = parse();
+fn part_1(input: &str) -> isize {
+    let monkeys = parse(input);
     calc_monkey("root", &monkeys)
 }
 
-fn part_2() -> isize {
-    let monkeys = parse();
+fn part_2(input: &str) -> isize {
+    let monkeys = parse(input);
     let Monkey::Calculated(_, lhs, rhs) = monkeys["root"] else {
         panic!("Root monkey has to be calculated")
     };
@@ -126,6 +129,7 @@ fn part_2() -> isize {
 
 pub fn run() {
     println!("\n=== Day 21 ===");
-    println!("Part 1: {}", part_1());
-    println!("Part 2: {}", part_2());
+    let input = input::prepare(21, include_str!("input.txt"));
+    println!("Part 1: {}", part_1(&input));
+    println!("Part 2: {}", part_2(&input));
 }
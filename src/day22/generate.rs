@@ -0,0 +1,57 @@
+/// Random monkey maps folded from a cube net, plus a path to follow across them
+use std::fmt::Write;
+
+use crate::shared::rng::Rng;
+
+/// Some of the ways to unfold a cube, `#` marks a face
+const NETS: [&[&str]; 5] = [
+    // the real puzzle's
+    &[" ##", " # ", "## ", "#  "],
+    // the example's
+    &["  # ", "### ", "  ##"],
+    &[" #  ", "####", " #  "],
+    &["#   ", "####", "   #"],
+    &["##  ", " ## ", "  ##"],
+];
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let face = (size * 5).max(2);
+    let net = *rng.choose(&NETS);
+
+    let mut out = String::new();
+    let mut first_row = true;
+    for net_row in net {
+        for _ in 0..face {
+            let mut line = String::new();
+            for cell in net_row.trim_end().chars() {
+                for _ in 0..face {
+                    let tile = match cell {
+                        ' ' => ' ',
+                        // the path starts at the first open tile of the top row
+                        _ if first_row && !line.contains('.') => '.',
+                        _ if rng.chance(0.1) => '#',
+                        _ => '.',
+                    };
+                    line.push(tile);
+                }
+            }
+            writeln!(out, "{line}").unwrap();
+            first_row = false;
+        }
+    }
+
+    out.push('\n');
+    for step in 0..(size * 200).max(1) {
+        if step > 0 {
+            out.push(*rng.choose(&['L', 'R']));
+        }
+        write!(out, "{}", rng.range(1..=50)).unwrap();
+    }
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    super::parse(input);
+}
@@ -1,7 +1,9 @@
+pub(crate) mod generate;
 mod shared;
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use crate::shared::input;
 use itertools::Itertools;
 use nom::{
     branch::alt,
@@ -275,7 +277,16 @@ fn part_1(input: &str) -> usize {
 
 pub fn run() {
     println!("\n=== Day 22 ===");
-    let input = include_str!("input.txt");
-    println!("Part 1: {}", part_1(input));
+    let input = input::prepare(22, include_str!("input.txt"));
+    println!("Part 1: {}", part_1(&input));
     println!("Part 2: TODO!!");
 }
+
+#[test]
+fn test_crlf_input() {
+    use crate::shared::rng::Rng;
+
+    let lf = generate::generate(&mut Rng::new(0), 2);
+    let crlf = lf.replace('\n', "\r\n");
+    assert_eq!(part_1(&input::prepare(22, &crlf)), part_1(&lf));
+}
@@ -0,0 +1,17 @@
+/// A random square patch of ground with elves scattered over about half of it
+use crate::shared::rng::Rng;
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let side = (size * 7).max(2);
+    let mut out = String::with_capacity(side * (side + 1));
+    for _ in 0..side {
+        out.extend((0..side).map(|_| if rng.chance(0.5) { '#' } else { '.' }));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    assert!(!super::Grove::parse(input).is_empty());
+}
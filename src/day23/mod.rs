@@ -1,5 +1,7 @@
+use crate::shared::input;
 use debug_print::debug_println;
 use std::collections::{HashMap, HashSet};
+pub(crate) mod generate;
 mod shared;
 const N: [(isize, isize); 3] = [(0, -1), (1, -1), (-1, -1)]; // Look N, NE, NW
 const S: [(isize, isize); 3] = [(0, 1), (1, 1), (-1, 1)]; // Look S, SE, SW
@@ -43,7 +45,7 @@ impl Grove {
             .collect()
     }
 
-    fn propose_move(&self, elf_pos: Point, other_elves_pos: &[Point]) -> Option<Point> {
+    fn propose_move(&self, elf_pos: Point, other_elves_pos: &HashSet<Point>) -> Option<Point> {
         // represent all spaces with an elf in them surrounding this elf as false, empty as true
         // f t t
         // f e t
@@ -54,7 +56,7 @@ impl Grove {
             .map(|d| d.map(|pt| !other_elves_pos.contains(&(elf_pos + pt))))
             .collect::<Vec<[bool; 3]>>();
         // if all points around the elf are empty (false), return None
-        let all_empty = valid_spaces.flatten().iter().all(|pt| *pt);
+        let all_empty = valid_spaces.as_flattened().iter().all(|pt| *pt);
         if all_empty {
             None
         } else {
@@ -76,8 +78,9 @@ impl Grove {
         }
     }
 
+    /// Whether any elf ends up with a move, once proposals for the same spot cancel out
     fn get_proposed_moves(&mut self) -> bool {
-        let elf_positions: Vec<Point> = self.elf_map.values().cloned().collect();
+        let elf_positions: HashSet<Point> = self.elf_map.values().cloned().collect();
         for elf_id in 1..=self.elf_map.len() {
             if let Some(mov) = self.propose_move(self.elf_map[&elf_id], &elf_positions) {
                 if !self.proposals.insert(mov) {
@@ -88,11 +91,8 @@ impl Grove {
                 }
             }
         }
-        if self.proposals.is_empty() {
-            return false;
-        }
         self.proposals.clear();
-        true
+        !self.moves.is_empty()
     }
 
     fn move_elves(&mut self) -> bool {
@@ -155,16 +155,24 @@ impl From<&str> for Grove {
 
 pub fn run() {
     println!("\n=== Day 23 ===");
-    let input = include_str!("input.txt");
-    let mut grove = Grove::from(input);
+    let input = input::prepare(23, include_str!("input.txt"));
+    let mut grove = Grove::from(input.as_str());
     while grove.move_elves() {
         debug_println!("\x1B[2J\x1B[1;1H");
         debug_println!("{}", grove.get_map_string());
         if grove.step == 10 {
             println!("Part 1: {}", grove.get_empty_area());
-            break;
         }
     }
-    // println!("Part 2: {}", grove.step + 1)
-    println!("Part 2: Too slow! need to speed it up.")
+    // the round nobody moved in didn't count as a step
+    println!("Part 2: {}", grove.step + 1)
+}
+
+#[test]
+fn test_sample() {
+    let mut grove = Grove::from(include_str!("sample_input.txt"));
+    while grove.step < 10 && grove.move_elves() {}
+    assert_eq!(grove.get_empty_area(), 110);
+    while grove.move_elves() {}
+    assert_eq!(grove.step + 1, 20);
 }
@@ -18,7 +18,7 @@ use crate::shared::sleep_s;
 use std::{io::stdout, isize, mem::swap};
 
 pub(super) const WALL: u8 = 0b0001_0000;
-// pub(super) const EXPEDITION: u8 = 0b0010_0000;
+pub(super) const EXPEDITION: u8 = 0b0010_0000;
 pub(super) const UP: u8 = 0b0000_1000;
 pub(super) const DOWN: u8 = 0b0000_0100;
 pub(super) const LEFT: u8 = 0b0000_0010;
@@ -241,8 +241,9 @@ impl MapState {
         true
     }
 
-    pub fn is_valid(&self, pos: Point) -> bool {
-        self.in_bounds((pos.x as isize, pos.y as isize)) && self.get_cur_tile(pos) == 0
+    /// Check against any snapshot of the tiles, not just the current `state`
+    pub fn is_valid_at(&self, pos: Point, tiles: &[u8]) -> bool {
+        self.in_bounds((pos.x as isize, pos.y as isize)) && tiles[pos.y * self.width + pos.x] == 0
     }
 
     pub fn display(&self) -> CtermResult<()> {
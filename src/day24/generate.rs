@@ -0,0 +1,53 @@
+/// Random blizzard valleys. No blizzard goes up or down the entrance or exit columns, since it
+/// would have nowhere to go once it reached the gap in the wall. Small valleys can easily end up
+/// with no way through, so maps are rerolled until the expedition can make all three trips.
+use crate::shared::rng::Rng;
+
+use super::{common::MapState, plan_route};
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let width = (size * 12).max(3);
+    let height = (size * 5 / 2).max(2);
+    loop {
+        let valley = valley(rng, width, height);
+        let state = MapState::from(valley.as_str());
+        let trips = [state.start, state.goal, state.start, state.goal];
+        if plan_route(&state, &trips).is_some() {
+            return valley;
+        }
+    }
+}
+
+fn valley(rng: &mut Rng, width: usize, height: usize) -> String {
+    let mut out = String::with_capacity((width + 3) * (height + 2));
+    let wall = |gap: usize| {
+        (0..width + 2)
+            .map(|x| if x == gap { '.' } else { '#' })
+            .collect::<String>()
+    };
+    out.push_str(&wall(1));
+    out.push('\n');
+    for _ in 0..height {
+        out.push('#');
+        for x in 1..=width {
+            let vertical_ok = x != 1 && x != width;
+            let tile = match rng.index(8) {
+                0 => '<',
+                1 => '>',
+                2 if vertical_ok => '^',
+                3 if vertical_ok => 'v',
+                _ => '.',
+            };
+            out.push(tile);
+        }
+        out.push_str("#\n");
+    }
+    out.push_str(&wall(width));
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    let _ = super::common::MapState::from(input);
+}
@@ -1,10 +1,18 @@
 mod common;
+pub(crate) mod generate;
+use crate::shared::{
+    input,
+    search::{
+        timed::{timed_route, Frames},
+        Path,
+    },
+};
 use common::*;
 use crossterm::{
     self, execute,
     terminal::{Clear, ClearType},
 };
-use std::{collections::HashSet, io::stdout};
+use std::io::stdout;
 
 const DEBUG: bool = false;
 
@@ -12,66 +20,62 @@ fn clear_screen() -> crossterm::Result<()> {
     execute!(stdout(), Clear(ClearType::All))
 }
 
-fn bfs(state: &mut MapState) {
-    let start = state.start;
-    let goal = state.goal;
-
-    let mut frontier = HashSet::new();
-    frontier.insert(start);
+/// Blizzards wrap around the valley, so their layout repeats every lcm(inner width, inner height)
+/// minutes. Record each distinct layout once and look them up by time.
+fn blizzard_frames(state: &MapState) -> Frames<Vec<u8>> {
+    let mut sim = state.clone();
+    Frames::record(state.state.clone(), |tiles| {
+        sim.state.clone_from(tiles);
+        sim.move_blizzards();
+        sim.state.clone()
+    })
+}
 
-    while !frontier.contains(&goal) {
-        state.move_blizzards();
-        if DEBUG {
-            _ = state.display();
-        }
-        frontier = HashSet::from_iter(explore_frontier(state, &frontier));
-    }
+/// Walk the expedition through each waypoint in turn, returning the path for every leg
+fn plan_route(state: &MapState, waypoints: &[Point]) -> Option<Vec<Path<(Point, usize), usize>>> {
+    let frames = blizzard_frames(state);
+    timed_route(
+        waypoints,
+        0,
+        frames.period(),
+        // can always wait in place, as long as a blizzard doesn't move in
+        |loc| {
+            let mut moves = loc.adjacent();
+            moves.push(*loc);
+            moves
+        },
+        |loc, time| state.is_valid_at(*loc, frames.at(time)),
+    )
 }
 
-fn explore_frontier(cur_state: &MapState, frontier: &HashSet<Point>) -> Vec<Point> {
-    let mut valid = vec![];
-    for loc in frontier {
-        for neighbor in loc.adjacent() {
-            if cur_state.is_valid(neighbor) {
-                valid.push(neighbor);
-            }
-        }
-        if cur_state.is_valid(*loc) {
-            valid.push(*loc);
-        }
+#[allow(unused_must_use)]
+fn show_route(state: &MapState, legs: &[Path<(Point, usize), usize>]) {
+    let frames = blizzard_frames(state);
+    let mut display = state.clone();
+    clear_screen();
+    for (loc, time) in legs.iter().flat_map(|leg| leg.nodes.iter()) {
+        display.state.clone_from(frames.at(*time));
+        display.time = *time;
+        display.set_cur_tile(*loc, EXPEDITION);
+        display.display();
     }
-    valid
 }
 
-#[allow(unused_must_use)]
 pub fn run() {
     println!("\n=== Day 24 ===");
-    let input = include_str!("input.txt");
-    let mut state = MapState::from(input);
-    let mut leg_times = vec![];
-
-    if DEBUG {
-        clear_screen();
-    }
-
-    bfs(&mut state);
-    leg_times.push(state.time);
-    println!("Part 1: {}", state.time);
+    let input = input::prepare(24, include_str!("input.txt"));
+    let state = MapState::from(input.as_str());
 
     // Part two, go back to start & then back to end again bc
     // elves forgot their snacks.
+    let legs = plan_route(&state, &[state.start, state.goal, state.start, state.goal])
+        .expect("no path through the blizzards");
 
-    // Go back to start
-    std::mem::swap(&mut state.start, &mut state.goal);
-    bfs(&mut state);
-    leg_times.push(state.time - leg_times.iter().sum::<usize>());
-
-    // Go back to end
-    std::mem::swap(&mut state.start, &mut state.goal);
-    bfs(&mut state);
-    leg_times.push(state.time - leg_times.iter().sum::<usize>());
     if DEBUG {
+        show_route(&state, &legs);
+        let leg_times = legs.iter().map(|leg| leg.cost).collect::<Vec<_>>();
         println!("Leg times: {leg_times:?}");
     }
-    println!("Part 2: {}", state.time);
+    println!("Part 1: {}", legs[0].goal().1);
+    println!("Part 2: {}", legs[2].goal().1);
 }
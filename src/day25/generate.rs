@@ -0,0 +1,24 @@
+/// Random SNAFU numbers, small enough that their sum still fits in an `i64`
+use crate::shared::rng::Rng;
+
+const DIGITS: [char; 5] = ['=', '-', '0', '1', '2'];
+
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..(size * 12).max(1) {
+        // no leading zeros, and a positive number
+        out.push(*rng.choose(&['1', '2']));
+        for _ in 0..rng.range(0..=19) {
+            out.push(*rng.choose(&DIGITS));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) fn check(input: &str) {
+    input.lines().for_each(|line| {
+        super::to_decimal(line);
+    });
+}
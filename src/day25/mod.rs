@@ -1,3 +1,7 @@
+pub(crate) mod generate;
+
+use crate::shared::input;
+
 fn to_decimal(snafu: &str) -> i64 {
     snafu.chars().fold(0, |decimal, snafu_digit| {
         // Example: if char is '-', position is 1. Subtract 2 to get -1
@@ -35,8 +39,67 @@ fn to_snafu(decimal: i64) -> String {
 
 pub fn run() {
     println!("\n=== Day 25 ===");
-    let input = include_str!("input.txt");
+    let input = input::prepare(25, include_str!("input.txt"));
     let part1 = input.lines().map(to_decimal).sum::<i64>();
     println!("Part 1: {}", to_snafu(part1));
     println!("Part 2: Merry Christmas!!");
 }
+
+/// Textbook base conversion: plain base 5 digits first, then borrow from the next digit up
+/// wherever a 3 or a 4 shows up
+#[cfg(test)]
+fn naive_to_snafu(mut decimal: i64) -> String {
+    let mut digits = vec![];
+    while decimal > 0 {
+        digits.push(decimal % 5);
+        decimal /= 5;
+    }
+    digits.push(0);
+    for i in 0..digits.len() - 1 {
+        if digits[i] > 2 {
+            digits[i] -= 5;
+            digits[i + 1] += 1;
+        }
+    }
+    while digits.len() > 1 && digits.last() == Some(&0) {
+        digits.pop();
+    }
+    let snafu = digits
+        .iter()
+        .rev()
+        .map(|d| ['=', '-', '0', '1', '2'][(d + 2) as usize]);
+    snafu.collect()
+}
+
+#[cfg(test)]
+fn naive_to_decimal(snafu: &str) -> i64 {
+    snafu
+        .chars()
+        .rev()
+        .enumerate()
+        .map(|(place, c)| {
+            let digit = "=-012".find(c).unwrap() as i64 - 2;
+            digit * 5_i64.pow(place as u32)
+        })
+        .sum()
+}
+
+#[test]
+fn test_snafu_matches_naive() {
+    use crate::shared::prop::{agree, check, shrink_int};
+
+    check(
+        "day25 snafu",
+        |rng| match rng.chance(0.5) {
+            true => rng.range(1..=1000),
+            false => rng.range(1..=1_000_000_000_000_000),
+        },
+        |n| shrink_int(n).into_iter().filter(|&n| n > 0).collect(),
+        |&n| {
+            let snafu = to_snafu(n);
+            agree(&snafu, &naive_to_snafu(n))?;
+            agree(to_decimal(&snafu), naive_to_decimal(&snafu))?;
+            agree(to_decimal(&snafu), n)
+        },
+    );
+}
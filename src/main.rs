@@ -1,8 +1,8 @@
-#![feature(array_chunks)]
 #![feature(iter_array_chunks)]
-#![feature(slice_flatten)]
 use std::env::args;
 
+use shared::rng::Rng;
+
 #[allow(dead_code)]
 mod shared;
 
@@ -22,7 +22,6 @@ mod day13;
 mod day14;
 mod day15;
 mod day16;
-#[allow(clippy::all, dead_code, unused_variables)]
 mod day17;
 mod day18;
 mod day19;
@@ -61,10 +60,78 @@ const DAYS: [fn(); 25] = [
     day25::run,
 ];
 
+const GENERATORS: [fn(&mut Rng, usize) -> String; 25] = [
+    day01::generate::generate,
+    day02::generate::generate,
+    day03::generate::generate,
+    day04::generate::generate,
+    day05::generate::generate,
+    day06::generate::generate,
+    day07::generate::generate,
+    day08::generate::generate,
+    day09::generate::generate,
+    day10::generate::generate,
+    day11::generate::generate,
+    day12::generate::generate,
+    day13::generate::generate,
+    day14::generate::generate,
+    day15::generate::generate,
+    day16::generate::generate,
+    day17::generate::generate,
+    day18::generate::generate,
+    day19::generate::generate,
+    day20::generate::generate,
+    day21::generate::generate,
+    day22::generate::generate,
+    day23::generate::generate,
+    day24::generate::generate,
+    day25::generate::generate,
+];
+
+/// What each day's generated inputs have to get through: its parser, and whatever else the
+/// solution relies on
+#[cfg(test)]
+const CHECKS: [fn(&str); 25] = [
+    day01::generate::check,
+    day02::generate::check,
+    day03::generate::check,
+    day04::generate::check,
+    day05::generate::check,
+    day06::generate::check,
+    day07::generate::check,
+    day08::generate::check,
+    day09::generate::check,
+    day10::generate::check,
+    day11::generate::check,
+    day12::generate::check,
+    day13::generate::check,
+    day14::generate::check,
+    day15::generate::check,
+    day16::generate::check,
+    day17::generate::check,
+    day18::generate::check,
+    day19::generate::check,
+    day20::generate::check,
+    day21::generate::check,
+    day22::generate::check,
+    day23::generate::check,
+    day24::generate::check,
+    day25::generate::check,
+];
+
+/// `--size` when none is given, about as big as the real puzzle inputs
+const DEFAULT_SIZE: usize = 10;
+
 fn parse_input() {
     if let Some(input) = args().nth(1) {
         if input.to_lowercase() == "all" {
             run_all()
+        } else if input == "generate" {
+            generate(&args().skip(2).collect::<Vec<_>>())
+        } else if input == "asm" || input == "disasm" {
+            day10::asm::cli(&input, &args().skip(2).collect::<Vec<_>>())
+        } else if input == "fs" {
+            day07::query::cli(&args().skip(2).collect::<Vec<_>>())
         } else if let Ok(day) = input.parse::<isize>() {
             if day <= 0 || day > 25 {
                 println!("Not a valid day. Running all days.");
@@ -78,6 +145,29 @@ fn parse_input() {
     }
 }
 
+/// `generate <day> [--seed S] [--size N]`: print a random input for `day` that its solution can
+/// parse. The same seed always gives the same input.
+fn generate(args: &[String]) {
+    let usage = "usage: generate <day> [--seed S] [--size N]";
+    let day = match args.first().and_then(|day| day.parse::<usize>().ok()) {
+        Some(day @ 1..=25) => day,
+        _ => return eprintln!("{usage}"),
+    };
+    let (mut seed, mut size) = (0, DEFAULT_SIZE);
+    let mut flags = args[1..].iter();
+    while let Some(flag) = flags.next() {
+        match (
+            flag.as_str(),
+            flags.next().and_then(|v| v.parse::<u64>().ok()),
+        ) {
+            ("--seed", Some(val)) => seed = val,
+            ("--size", Some(val)) if val > 0 => size = val as usize,
+            _ => return eprintln!("{usage}"),
+        }
+    }
+    print!("{}", GENERATORS[day - 1](&mut Rng::new(seed), size));
+}
+
 fn run_all() {
     for day in DAYS {
         day();
@@ -88,3 +178,10 @@ fn main() {
     color_eyre::install().unwrap();
     parse_input();
 }
+
+#[test]
+fn test_generated_inputs_parse() {
+    for (day, (generate, check)) in GENERATORS.into_iter().zip(CHECKS).enumerate() {
+        shared::prop::parse_generated(day + 1, generate, check);
+    }
+}
@@ -8,6 +8,25 @@ use std::{
     path::Path,
 };
 
+pub(crate) mod bitset;
+pub(crate) mod cycle;
+pub(crate) mod flood;
+pub(crate) mod graph;
+pub(crate) mod input;
+pub(crate) mod interval;
+pub(crate) mod ocr;
+pub(crate) mod parse;
+pub(crate) mod prop;
+pub(crate) mod rng;
+pub(crate) mod search;
+pub(crate) mod sparse;
+pub(crate) mod transform;
+pub(crate) mod vm;
+pub(crate) mod voxel;
+
+pub(crate) use bitset::{BitSet64, Interner};
+pub(crate) use interval::IntervalSet;
+
 custom_error! {pub GridError
     OutOfBounds = "coord was out of bounds",
     NotFound = "Not found"
@@ -0,0 +1,431 @@
+/// Compact sets of small integers, stored as bits.
+///
+/// `BitSet64` and `BitSet128` are `Copy` and hash as a single integer, which makes them cheap
+/// search state keys (e.g. "which valves are open"). `DynBitSet` grows as needed for when the
+/// universe doesn't fit in 128 bits. `Interner` hands out the bit indices for named things.
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    hash::Hash,
+    ops::{BitAnd, BitOr, BitXor, Not, Shl, Sub},
+};
+
+/// Unsigned integer types a `BitSet` can be backed by
+pub(crate) trait Word:
+    Copy
+    + Eq
+    + Ord
+    + Hash
+    + Debug
+    + Default
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Sub<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const BITS: usize;
+    fn count_ones(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {
+        $(impl Word for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const BITS: usize = <$t>::BITS as usize;
+            fn count_ones(self) -> u32 {
+                <$t>::count_ones(self)
+            }
+            fn trailing_zeros(self) -> u32 {
+                <$t>::trailing_zeros(self)
+            }
+        })*
+    };
+}
+
+impl_word!(u8, u16, u32, u64, u128);
+
+/// Fixed size set of the integers `0..W::BITS`
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct BitSet<W>(pub W);
+
+pub(crate) type BitSet64 = BitSet<u64>;
+pub(crate) type BitSet128 = BitSet<u128>;
+
+impl<W: Word> Debug for BitSet<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<W: Word> FromIterator<usize> for BitSet<W> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for i in iter {
+            set.insert(i);
+        }
+        set
+    }
+}
+
+impl<W: Word> BitSet<W> {
+    pub(crate) fn new() -> Self {
+        Self(W::ZERO)
+    }
+
+    /// The set `{0, 1, .., n - 1}`
+    pub(crate) fn full(n: usize) -> Self {
+        assert!(n <= W::BITS, "{n} bits don't fit in a {} bit set", W::BITS);
+        if n == W::BITS {
+            Self(!W::ZERO)
+        } else {
+            Self((W::ONE << n) - W::ONE)
+        }
+    }
+
+    fn bit(i: usize) -> W {
+        assert!(
+            i < W::BITS,
+            "bit {i} out of range for a {} bit set",
+            W::BITS
+        );
+        W::ONE << i
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0 == W::ZERO
+    }
+
+    pub(crate) fn contains(&self, i: usize) -> bool {
+        i < W::BITS && self.0 & Self::bit(i) != W::ZERO
+    }
+
+    /// Returns true if `i` wasn't already in the set, like `HashSet::insert`
+    pub(crate) fn insert(&mut self, i: usize) -> bool {
+        let was_in = self.contains(i);
+        self.0 = self.0 | Self::bit(i);
+        !was_in
+    }
+
+    pub(crate) fn remove(&mut self, i: usize) -> bool {
+        let was_in = self.contains(i);
+        self.0 = self.0 & !Self::bit(i);
+        was_in
+    }
+
+    /// Copy of the set with `i` added
+    pub(crate) fn with(mut self, i: usize) -> Self {
+        self.insert(i);
+        self
+    }
+
+    pub(crate) fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub(crate) fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub(crate) fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    pub(crate) fn symmetric_difference(self, other: Self) -> Self {
+        Self(self.0 ^ other.0)
+    }
+
+    pub(crate) fn is_subset(self, other: Self) -> bool {
+        self.0 & !other.0 == W::ZERO
+    }
+
+    pub(crate) fn is_superset(self, other: Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub(crate) fn is_disjoint(self, other: Self) -> bool {
+        self.0 & other.0 == W::ZERO
+    }
+
+    /// Members in increasing order
+    pub(crate) fn iter(self) -> impl Iterator<Item = usize> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            if bits == W::ZERO {
+                return None;
+            }
+            let i = bits.trailing_zeros() as usize;
+            // clear the lowest set bit
+            bits = bits & (bits - W::ONE);
+            Some(i)
+        })
+    }
+
+    /// Every subset of this set (all `2^len` of them), starting with the full set and ending
+    /// with the empty set
+    pub(crate) fn subsets(self) -> impl Iterator<Item = Self> {
+        let mask = self.0;
+        let mut next = Some(mask);
+        std::iter::from_fn(move || {
+            let sub = next?;
+            next = if sub == W::ZERO {
+                None
+            } else {
+                Some((sub - W::ONE) & mask)
+            };
+            Some(Self(sub))
+        })
+    }
+}
+
+impl<W: Word> BitOr for BitSet<W> {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl<W: Word> BitAnd for BitSet<W> {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl<W: Word> Sub for BitSet<W> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+/// Growable bit set. Trailing empty words are always trimmed, so equal sets compare and hash equal.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub(crate) struct DynBitSet {
+    words: Vec<u64>,
+}
+
+impl Debug for DynBitSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<usize> for DynBitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for i in iter {
+            set.insert(i);
+        }
+        set
+    }
+}
+
+impl DynBitSet {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+
+    fn zip_words(&self, other: &Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        let len = self.words.len().max(other.words.len());
+        let word = |words: &[u64], i: usize| words.get(i).copied().unwrap_or(0);
+        let mut res = Self {
+            words: (0..len)
+                .map(|i| f(word(&self.words, i), word(&other.words, i)))
+                .collect(),
+        };
+        res.trim();
+        res
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub(crate) fn contains(&self, i: usize) -> bool {
+        self.words
+            .get(i / 64)
+            .is_some_and(|w| w & (1 << (i % 64)) != 0)
+    }
+
+    pub(crate) fn insert(&mut self, i: usize) -> bool {
+        if self.words.len() <= i / 64 {
+            self.words.resize(i / 64 + 1, 0);
+        }
+        let was_in = self.contains(i);
+        self.words[i / 64] |= 1 << (i % 64);
+        !was_in
+    }
+
+    pub(crate) fn remove(&mut self, i: usize) -> bool {
+        let was_in = self.contains(i);
+        if was_in {
+            self.words[i / 64] &= !(1 << (i % 64));
+            self.trim();
+        }
+        was_in
+    }
+
+    pub(crate) fn union(&self, other: &Self) -> Self {
+        self.zip_words(other, |a, b| a | b)
+    }
+
+    pub(crate) fn intersection(&self, other: &Self) -> Self {
+        self.zip_words(other, |a, b| a & b)
+    }
+
+    pub(crate) fn difference(&self, other: &Self) -> Self {
+        self.zip_words(other, |a, b| a & !b)
+    }
+
+    pub(crate) fn symmetric_difference(&self, other: &Self) -> Self {
+        self.zip_words(other, |a, b| a ^ b)
+    }
+
+    pub(crate) fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub(crate) fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub(crate) fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).is_empty()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(w, &bits)| BitSet(bits).iter().map(move |i| w * 64 + i))
+    }
+
+    /// Every subset of this set, starting with the full set and ending with the empty set.
+    /// Only practical for small sets, panics with more than 63 members.
+    pub(crate) fn subsets(&self) -> impl Iterator<Item = DynBitSet> + '_ {
+        let members = self.iter().collect::<Vec<_>>();
+        assert!(members.len() < 64, "too many members to enumerate subsets");
+        BitSet::<u64>::full(members.len())
+            .subsets()
+            .map(move |sub| sub.iter().map(|i| members[i]).collect())
+    }
+}
+
+/// Hands out consecutive indices to distinct keys, e.g. to turn valve names into bit positions
+#[derive(Debug, Clone)]
+pub(crate) struct Interner<K> {
+    index: HashMap<K, usize>,
+    keys: Vec<K>,
+}
+
+impl<K> Default for Interner<K> {
+    fn default() -> Self {
+        Self {
+            index: HashMap::new(),
+            keys: vec![],
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone> FromIterator<K> for Interner<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut interner = Self::new();
+        for key in iter {
+            interner.intern(key);
+        }
+        interner
+    }
+}
+
+impl<K: Eq + Hash + Clone> Interner<K> {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index of `key`, giving it the next free one if it's new
+    pub(crate) fn intern(&mut self, key: K) -> usize {
+        if let Some(&i) = self.index.get(&key) {
+            return i;
+        }
+        let i = self.keys.len();
+        self.index.insert(key.clone(), i);
+        self.keys.push(key);
+        i
+    }
+
+    pub(crate) fn get(&self, key: &K) -> Option<usize> {
+        self.index.get(key).copied()
+    }
+
+    /// Key for an index handed out earlier
+    pub(crate) fn key(&self, i: usize) -> &K {
+        &self.keys[i]
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub(crate) fn keys(&self) -> &[K] {
+        &self.keys
+    }
+}
+
+#[test]
+fn test_fixed_bitset() {
+    let a: BitSet64 = [1, 3, 5].into_iter().collect();
+    let b: BitSet64 = [3, 4].into_iter().collect();
+    assert_eq!((a | b).iter().collect::<Vec<_>>(), vec![1, 3, 4, 5]);
+    assert_eq!((a & b).iter().collect::<Vec<_>>(), vec![3]);
+    assert_eq!((a - b).len(), 2);
+    assert!(BitSet64::new().with(3).is_subset(a));
+    assert!(a.difference(b).is_disjoint(b));
+    assert_eq!(BitSet128::full(128).len(), 128);
+    assert!(BitSet128::new().with(127).contains(127));
+    let subsets = a.subsets().collect::<Vec<_>>();
+    assert_eq!(subsets.len(), 8);
+    assert!(subsets.iter().all(|s| s.is_subset(a)));
+    assert_eq!(subsets.last(), Some(&BitSet64::new()));
+}
+
+#[test]
+fn test_dyn_bitset_and_interner() {
+    let mut a: DynBitSet = [0, 70, 200].into_iter().collect();
+    let b: DynBitSet = [70].into_iter().collect();
+    assert_eq!(a.len(), 3);
+    assert!(b.is_subset(&a));
+    assert_eq!(a.difference(&b).iter().collect::<Vec<_>>(), vec![0, 200]);
+    a.remove(200);
+    assert_eq!(a, [70, 0].into_iter().collect());
+    assert_eq!(a.subsets().count(), 4);
+
+    let mut names: Interner<&str> = ["AA", "BB"].into_iter().collect();
+    assert_eq!(names.intern("CC"), 2);
+    assert_eq!(names.intern("AA"), 0);
+    assert_eq!(*names.key(1), "BB");
+    assert_eq!(names.get(&"DD"), None);
+}
@@ -0,0 +1,252 @@
+/// Cycle detection for simulations that are too long to run to the end.
+///
+/// Once a simulation revisits a state, everything after it repeats. If we know where the cycle
+/// starts and how long it is, a running metric (tower height, number of moves, ...) can be
+/// extrapolated to any step count with a little arithmetic.
+///
+/// `floyd` and `brent` need the whole state to be comparable and only keep a couple of states
+/// around. `CycleDetector` instead remembers a (usually much smaller) fingerprint of every step,
+/// for when the full state keeps growing but its "shape" repeats.
+use std::{collections::HashMap, hash::Hash};
+
+/// `start` is the first step that's part of the cycle, `length` the number of steps in it.
+/// So state `start + length` == state `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Step in the first pass through the cycle that has the same state as step `n`
+    pub(crate) fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// Extrapolate a cumulative metric to step `n`. `metrics[i]` is the metric after step `i`,
+    /// and must cover at least `start + length` steps.
+    pub(crate) fn extrapolate(&self, metrics: &[i64], n: usize) -> i64 {
+        assert!(
+            metrics.len() > self.start + self.length,
+            "need metrics up to the end of the first cycle"
+        );
+        if n < metrics.len() {
+            return metrics[n];
+        }
+        let per_cycle = metrics[self.start + self.length] - metrics[self.start];
+        let full_cycles = ((n - self.start) / self.length) as i64;
+        metrics[self.equivalent_step(n)] + full_cycles * per_cycle
+    }
+}
+
+/// Floyd's tortoise and hare
+pub(crate) fn floyd<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: PartialEq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // hare is now a multiple of the cycle length ahead, walk both at the same speed
+    // from the start and they meet at the first state of the cycle
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Brent's algorithm, usually fewer calls to `step` than Floyd's
+pub(crate) fn brent<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: PartialEq + Clone,
+    F: FnMut(&S) -> S,
+{
+    // find the length by teleporting the tortoise to the hare at every power of 2
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // then start the hare `length` steps ahead, they meet at the start of the cycle
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Records a fingerprint and metric for every step, and spots the first repeated fingerprint.
+#[derive(Debug)]
+pub(crate) struct CycleDetector<K> {
+    seen: HashMap<K, usize>,
+    metrics: Vec<i64>,
+    cycle: Option<Cycle>,
+}
+
+impl<K> Default for CycleDetector<K> {
+    fn default() -> Self {
+        Self {
+            seen: HashMap::new(),
+            metrics: vec![],
+            cycle: None,
+        }
+    }
+}
+
+impl<K> CycleDetector<K>
+where
+    K: Eq + Hash,
+{
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the next step. Returns the cycle once the fingerprint has been seen before.
+    pub(crate) fn record(&mut self, fingerprint: K, metric: i64) -> Option<Cycle> {
+        let step = self.metrics.len();
+        self.metrics.push(metric);
+        if self.cycle.is_none() {
+            if let Some(&prev) = self.seen.get(&fingerprint) {
+                self.cycle = Some(Cycle {
+                    start: prev,
+                    length: step - prev,
+                });
+            } else {
+                self.seen.insert(fingerprint, step);
+            }
+        }
+        self.cycle
+    }
+
+    pub(crate) fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    pub(crate) fn metrics(&self) -> &[i64] {
+        &self.metrics
+    }
+
+    /// Metric at step `n`, either recorded or extrapolated through the cycle
+    pub(crate) fn extrapolate(&self, n: usize) -> Option<i64> {
+        match self.cycle {
+            Some(cycle) => Some(cycle.extrapolate(&self.metrics, n)),
+            None => self.metrics.get(n).copied(),
+        }
+    }
+}
+
+/// Run a simulation until its fingerprint repeats, then extrapolate `metric` to step `n`.
+///
+/// Step 0 is `initial` itself. Returns `None` if `n` is never reached and no cycle turns up within
+/// `max_steps`.
+pub(crate) fn extrapolate<S, K, FS, FK, FM>(
+    initial: S,
+    mut step: FS,
+    mut fingerprint: FK,
+    mut metric: FM,
+    n: usize,
+    max_steps: usize,
+) -> Option<i64>
+where
+    K: Eq + Hash,
+    FS: FnMut(&mut S),
+    FK: FnMut(&S) -> K,
+    FM: FnMut(&S) -> i64,
+{
+    let mut state = initial;
+    let mut detector = CycleDetector::new();
+    for i in 0..=max_steps {
+        // the repeated step itself gets recorded too, so the gain per cycle is known
+        if let Some(cycle) = detector.record(fingerprint(&state), metric(&state)) {
+            if i >= cycle.start + cycle.length {
+                return detector.extrapolate(n);
+            }
+        }
+        if i == n {
+            return Some(detector.metrics()[n]);
+        }
+        step(&mut state);
+    }
+    None
+}
+
+#[cfg(test)]
+/// 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 3 ...
+fn rho(x: &u32) -> u32 {
+    if *x == 6 {
+        3
+    } else {
+        x + 1
+    }
+}
+
+#[test]
+fn test_floyd_and_brent() {
+    let expected = Cycle {
+        start: 3,
+        length: 4,
+    };
+    assert_eq!(floyd(0, rho), expected);
+    assert_eq!(brent(0, rho), expected);
+    assert_eq!(brent(3, rho).start, 0);
+}
+
+#[test]
+fn test_extrapolate() {
+    // counter that keeps adding its (cycling) value to a running total
+    let total = extrapolate(
+        (0_u32, 0_i64),
+        |(x, sum)| {
+            *x = rho(x);
+            *sum += *x as i64;
+        },
+        |(x, _)| *x,
+        |(_, sum)| *sum,
+        1_000_000,
+        100,
+    )
+    .unwrap();
+    let mut brute = (0_u32, 0_i64);
+    for _ in 0..1_000_000 {
+        brute.0 = rho(&brute.0);
+        brute.1 += brute.0 as i64;
+    }
+    assert_eq!(total, brute.1);
+}
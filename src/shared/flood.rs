@@ -0,0 +1,254 @@
+/// Flood fill and connected-region labeling for any kind of grid.
+///
+/// A grid only has to implement `Fillable`: look up a cell, list a position's neighbors, and list
+/// every position it stores. `Grid`, `SparseGrid` and `voxel::Grid3` all do, so the same code
+/// finds cave pockets in 2D, elf clusters on an unbounded plane and air bubbles in 3D.
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use super::{sparse::SparseGrid, voxel::Vec3, Grid, GridCoord};
+
+/// Which cells count as touching
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Connectivity {
+    /// share an edge (2D, 4 neighbors) or a face (3D, 6 neighbors)
+    Orthogonal,
+    /// also touching diagonally (2D: 8 neighbors, 3D: 26 neighbors)
+    All,
+}
+
+/// Positions that can be combined into a bounding box
+pub(crate) trait Extent: Copy + Eq + Hash {
+    /// Component-wise minimum
+    fn lower(self, other: Self) -> Self;
+    /// Component-wise maximum
+    fn upper(self, other: Self) -> Self;
+}
+
+impl Extent for GridCoord {
+    fn lower(self, other: Self) -> Self {
+        (self.x.min(other.x), self.y.min(other.y)).into()
+    }
+
+    fn upper(self, other: Self) -> Self {
+        (self.x.max(other.x), self.y.max(other.y)).into()
+    }
+}
+
+impl Extent for (isize, isize) {
+    fn lower(self, other: Self) -> Self {
+        (self.0.min(other.0), self.1.min(other.1))
+    }
+
+    fn upper(self, other: Self) -> Self {
+        (self.0.max(other.0), self.1.max(other.1))
+    }
+}
+
+impl Extent for Vec3 {
+    fn lower(self, other: Self) -> Self {
+        self.min(other)
+    }
+
+    fn upper(self, other: Self) -> Self {
+        self.max(other)
+    }
+}
+
+pub(crate) trait Fillable {
+    type Pos: Extent;
+    type Cell;
+
+    /// `None` if the position is outside the grid (or not stored, for sparse grids)
+    fn get_cell(&self, pos: Self::Pos) -> Option<&Self::Cell>;
+
+    fn adjacent(&self, pos: Self::Pos, connectivity: Connectivity) -> Vec<Self::Pos>;
+
+    /// Every stored position, in a stable order
+    fn positions(&self) -> Vec<Self::Pos>;
+}
+
+impl<T> Fillable for Grid<T> {
+    type Pos = GridCoord;
+    type Cell = T;
+
+    fn get_cell(&self, pos: GridCoord) -> Option<&T> {
+        if pos.x < self.width && pos.y < self.height {
+            Some(&self.data[pos.y * self.width + pos.x])
+        } else {
+            None
+        }
+    }
+
+    fn adjacent(&self, pos: GridCoord, connectivity: Connectivity) -> Vec<GridCoord> {
+        let diag = connectivity == Connectivity::All;
+        super::get_neighbors((pos.x, pos.y), self.width, self.height, diag)
+            .unwrap()
+            .into_iter()
+            .map(GridCoord::from)
+            .collect()
+    }
+
+    fn positions(&self) -> Vec<GridCoord> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| GridCoord { x, y }))
+            .collect()
+    }
+}
+
+impl<T> Fillable for SparseGrid<T> {
+    type Pos = (isize, isize);
+    type Cell = T;
+
+    fn get_cell(&self, pos: (isize, isize)) -> Option<&T> {
+        self.get(pos)
+    }
+
+    fn adjacent(&self, pos: (isize, isize), connectivity: Connectivity) -> Vec<(isize, isize)> {
+        SparseGrid::<T>::adjacent(pos, connectivity == Connectivity::All)
+    }
+
+    fn positions(&self) -> Vec<(isize, isize)> {
+        let mut positions = self.positions().collect::<Vec<_>>();
+        positions.sort_by_key(|&(x, y)| (y, x));
+        positions
+    }
+}
+
+/// Every position reachable from `seeds` through cells where `passable` is true.
+/// Seeds that aren't passable (or aren't in the grid) are ignored.
+pub(crate) fn flood_fill<G, F>(
+    grid: &G,
+    seeds: impl IntoIterator<Item = G::Pos>,
+    connectivity: Connectivity,
+    passable: F,
+) -> HashSet<G::Pos>
+where
+    G: Fillable + ?Sized,
+    F: Fn(&G::Cell) -> bool,
+{
+    let is_open = |pos: G::Pos| grid.get_cell(pos).is_some_and(&passable);
+    let mut stack: Vec<G::Pos> = seeds.into_iter().filter(|pos| is_open(*pos)).collect();
+    let mut seen: HashSet<G::Pos> = stack.iter().copied().collect();
+    while let Some(pos) = stack.pop() {
+        for nbr in grid.adjacent(pos, connectivity) {
+            // `insert` returns true if was not previously in set
+            if is_open(nbr) && seen.insert(nbr) {
+                stack.push(nbr);
+            }
+        }
+    }
+    seen
+}
+
+/// One connected group of cells. `min` and `max` are the corners of its bounding box.
+#[derive(Debug, Clone)]
+pub(crate) struct Region<P> {
+    pub label: usize,
+    pub cells: Vec<P>,
+    pub min: P,
+    pub max: P,
+}
+
+impl<P> Region<P> {
+    pub(crate) fn size(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+/// Result of `label_regions`: which region every included cell belongs to
+#[derive(Debug, Clone)]
+pub(crate) struct Labeling<P> {
+    pub labels: HashMap<P, usize>,
+    pub regions: Vec<Region<P>>,
+}
+
+impl<P: Extent> Labeling<P> {
+    pub(crate) fn region_of(&self, pos: P) -> Option<&Region<P>> {
+        self.labels.get(&pos).map(|&label| &self.regions[label])
+    }
+
+    pub(crate) fn largest(&self) -> Option<&Region<P>> {
+        self.regions.iter().max_by_key(|r| r.size())
+    }
+}
+
+/// Split every cell where `include` is true into connected regions.
+/// Regions are labeled `0..n` in the order their first cell shows up in `positions()`, and each
+/// region's cells are kept in that same order.
+pub(crate) fn label_regions<G, F>(
+    grid: &G,
+    connectivity: Connectivity,
+    include: F,
+) -> Labeling<G::Pos>
+where
+    G: Fillable + ?Sized,
+    F: Fn(&G::Cell) -> bool,
+{
+    let positions = grid.positions();
+    let mut labels = HashMap::new();
+    let mut regions: Vec<Region<G::Pos>> = vec![];
+    for &pos in positions.iter() {
+        if labels.contains_key(&pos) || !grid.get_cell(pos).is_some_and(&include) {
+            continue;
+        }
+        let label = regions.len();
+        for cell in flood_fill(grid, [pos], connectivity, &include) {
+            labels.insert(cell, label);
+        }
+        regions.push(Region {
+            label,
+            cells: vec![],
+            min: pos,
+            max: pos,
+        });
+    }
+    // second pass so each region's cells come out in a stable order
+    for pos in positions {
+        if let Some(&label) = labels.get(&pos) {
+            let region = &mut regions[label];
+            region.cells.push(pos);
+            region.min = region.min.lower(pos);
+            region.max = region.max.upper(pos);
+        }
+    }
+    Labeling { labels, regions }
+}
+
+#[test]
+fn test_grid_regions() {
+    let grid = Grid {
+        width: 5,
+        height: 3,
+        data: "##..#\
+               #.#.#\
+               ....#"
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<Vec<char>>(),
+    };
+    let walls = label_regions(&grid, Connectivity::Orthogonal, |c| *c == '#');
+    assert_eq!(walls.regions.len(), 3);
+    assert_eq!(walls.regions[0].size(), 3);
+    assert_eq!(walls.regions[1].min, (4, 0).into());
+    assert_eq!(walls.regions[1].max, (4, 2).into());
+    // (2, 1) only touches the top left wall diagonally
+    let diag = label_regions(&grid, Connectivity::All, |c| *c == '#');
+    assert_eq!(diag.regions.len(), 2);
+    let floor = flood_fill(&grid, [(0, 2).into()], Connectivity::Orthogonal, |c| {
+        *c == '.'
+    });
+    assert_eq!(floor.len(), 8);
+}
+
+#[test]
+fn test_sparse_regions() {
+    let elves = SparseGrid::from_points([(0, 0), (1, 1), (5, 5), (5, 6), (-3, 0)], ());
+    let clusters = label_regions(&elves, Connectivity::All, |_| true);
+    assert_eq!(clusters.regions.len(), 3);
+    assert_eq!(clusters.region_of((1, 1)).unwrap().size(), 2);
+    assert_eq!(clusters.largest().unwrap().size(), 2);
+    assert_eq!(clusters.regions[0].cells, vec![(-3, 0)]);
+}
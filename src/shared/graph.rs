@@ -0,0 +1,272 @@
+/// A small labelled, weighted graph that's built up front (unlike the closure-based searches in
+/// `search`).
+///
+/// Nodes are interned, so edges and distances work on `usize` indices internally while the API
+/// takes labels. The main use is all-pairs distances and `contract`, which squashes a big graph
+/// down to the few nodes a search actually cares about (e.g. day16's flowing valves).
+use std::{
+    fmt::{Display, Write},
+    hash::Hash,
+    ops::Add,
+};
+
+use super::{bitset::Interner, search::bfs_distances};
+
+/// Edge weights. `Default` has to be zero.
+pub(crate) trait Weight: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<W: Copy + Ord + Default + Add<Output = W>> Weight for W {}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Graph<L, W> {
+    nodes: Interner<L>,
+    /// outgoing `(to, weight)` edges per node index
+    edges: Vec<Vec<(usize, W)>>,
+    directed: bool,
+}
+
+/// Shortest distance between every pair of nodes, `None` if unreachable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Distances<W> {
+    size: usize,
+    dist: Vec<Option<W>>,
+}
+
+impl<W: Copy> Distances<W> {
+    pub(crate) fn get(&self, from: usize, to: usize) -> Option<W> {
+        self.dist[from * self.size + to]
+    }
+}
+
+impl<L, W> Graph<L, W>
+where
+    L: Eq + Hash + Clone,
+    W: Weight,
+{
+    pub(crate) fn directed() -> Self {
+        Self {
+            nodes: Interner::new(),
+            edges: vec![],
+            directed: true,
+        }
+    }
+
+    /// Every edge added goes both ways
+    pub(crate) fn undirected() -> Self {
+        Self {
+            directed: false,
+            ..Self::directed()
+        }
+    }
+
+    pub(crate) fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Index of the node, adding it if it's new
+    pub(crate) fn add_node(&mut self, label: L) -> usize {
+        let idx = self.nodes.intern(label);
+        if idx == self.edges.len() {
+            self.edges.push(vec![]);
+        }
+        idx
+    }
+
+    /// Adds both nodes if needed. Adding the same edge twice keeps the lower weight.
+    pub(crate) fn add_edge(&mut self, from: L, to: L, weight: W) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.link(from, to, weight);
+        if !self.directed {
+            self.link(to, from, weight);
+        }
+    }
+
+    fn link(&mut self, from: usize, to: usize, weight: W) {
+        match self.edges[from].iter_mut().find(|(t, _)| *t == to) {
+            Some((_, w)) => *w = weight.min(*w),
+            None => self.edges[from].push((to, weight)),
+        }
+    }
+
+    pub(crate) fn index_of(&self, label: &L) -> Option<usize> {
+        self.nodes.get(label)
+    }
+
+    pub(crate) fn label(&self, idx: usize) -> &L {
+        self.nodes.key(idx)
+    }
+
+    pub(crate) fn labels(&self) -> &[L] {
+        self.nodes.keys()
+    }
+
+    /// Outgoing `(to, weight)` edges of a node
+    pub(crate) fn neighbors(&self, idx: usize) -> &[(usize, W)] {
+        &self.edges[idx]
+    }
+
+    /// Weight of the direct edge between two labels, if there is one
+    pub(crate) fn weight(&self, from: &L, to: &L) -> Option<W> {
+        let (from, to) = (self.index_of(from)?, self.index_of(to)?);
+        self.edges[from]
+            .iter()
+            .find(|(t, _)| *t == to)
+            .map(|&(_, w)| w)
+    }
+
+    /// All-pairs shortest paths, O(n^3)
+    pub(crate) fn floyd_warshall(&self) -> Distances<W> {
+        let n = self.len();
+        let mut dist = vec![None; n * n];
+        for (from, edges) in self.edges.iter().enumerate() {
+            dist[from * n + from] = Some(W::default());
+            for &(to, w) in edges {
+                let d: &mut Option<W> = &mut dist[from * n + to];
+                *d = Some(d.map_or(w, |d| d.min(w)));
+            }
+        }
+        for k in 0..n {
+            for i in 0..n {
+                let Some(ik) = dist[i * n + k] else { continue };
+                for j in 0..n {
+                    if let Some(kj) = dist[k * n + j] {
+                        let through_k = ik + kj;
+                        if dist[i * n + j].is_none_or(|ij| through_k < ij) {
+                            dist[i * n + j] = Some(through_k);
+                        }
+                    }
+                }
+            }
+        }
+        Distances { size: n, dist }
+    }
+
+    /// All-pairs distances counting hops and ignoring weights, one BFS per node.
+    /// Faster than `floyd_warshall` on sparse graphs where every edge costs the same.
+    pub(crate) fn bfs_all_pairs(&self) -> Distances<usize> {
+        let n = self.len();
+        let mut dist = vec![None; n * n];
+        for from in 0..n {
+            let reached = bfs_distances([from], |&curr| {
+                self.edges[curr]
+                    .iter()
+                    .map(|&(to, _)| to)
+                    .collect::<Vec<_>>()
+            });
+            for (to, d) in reached {
+                dist[from * n + to] = Some(d);
+            }
+        }
+        Distances { size: n, dist }
+    }
+
+    /// Graph with only `start` and the nodes matching `keep`, where every edge is the shortest
+    /// distance between two of them in this graph. Unreachable pairs and self loops are left out.
+    /// `start` is node 0 in the result.
+    pub(crate) fn contract<F>(&self, start: &L, keep: F) -> Self
+    where
+        F: Fn(&L) -> bool,
+    {
+        let dist = self.floyd_warshall();
+        let start = self.index_of(start).expect("start node isn't in the graph");
+        let kept = std::iter::once(start)
+            .chain((0..self.len()).filter(|&i| i != start && keep(self.label(i))))
+            .collect::<Vec<_>>();
+
+        let mut res = Self {
+            directed: self.directed,
+            ..Self::directed()
+        };
+        for &i in kept.iter() {
+            res.add_node(self.label(i).clone());
+        }
+        for &from in kept.iter() {
+            for &to in kept.iter() {
+                if let (true, Some(d)) = (from != to, dist.get(from, to)) {
+                    let (f, t) = (res.index_of(self.label(from)), res.index_of(self.label(to)));
+                    // add one direction at a time, `dist` is already symmetric if undirected
+                    res.link(f.unwrap(), t.unwrap(), d);
+                }
+            }
+        }
+        res
+    }
+}
+
+impl<L, W> Graph<L, W>
+where
+    L: Eq + Hash + Clone + Display,
+    W: Weight + Display,
+{
+    /// Graphviz source, render with e.g. `dot -Tsvg`
+    pub(crate) fn to_dot(&self, name: &str) -> String {
+        let (kind, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        let mut dot = format!("{kind} \"{name}\" {{\n");
+        for label in self.labels() {
+            writeln!(dot, "    \"{label}\";").unwrap();
+        }
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, w) in edges {
+                // undirected edges are stored twice, only write them once
+                if self.directed || from <= to {
+                    let (f, t) = (self.label(from), self.label(to));
+                    writeln!(dot, "    \"{f}\" {arrow} \"{t}\" [label=\"{w}\"];").unwrap();
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+/// A - B - C - D with a long shortcut A - D
+fn line_graph() -> Graph<&'static str, u32> {
+    let mut graph = Graph::undirected();
+    graph.add_edge("A", "B", 1);
+    graph.add_edge("B", "C", 2);
+    graph.add_edge("C", "D", 3);
+    graph.add_edge("A", "D", 10);
+    graph.add_node("E");
+    graph
+}
+
+#[test]
+fn test_all_pairs() {
+    let graph = line_graph();
+    let idx = |l| graph.index_of(&l).unwrap();
+    let dist = graph.floyd_warshall();
+    assert_eq!(dist.get(idx("A"), idx("D")), Some(6));
+    assert_eq!(dist.get(idx("D"), idx("A")), Some(6));
+    assert_eq!(dist.get(idx("A"), idx("E")), None);
+    let hops = graph.bfs_all_pairs();
+    assert_eq!(hops.get(idx("A"), idx("D")), Some(1));
+    assert_eq!(hops.get(idx("B"), idx("D")), Some(2));
+    assert_eq!(hops.get(idx("E"), idx("E")), Some(0));
+}
+
+#[test]
+fn test_contract_and_dot() {
+    let small = line_graph().contract(&"B", |l| *l == "D" || *l == "E");
+    assert_eq!(small.labels(), &["B", "D", "E"]);
+    assert_eq!(small.weight(&"B", &"D"), Some(5));
+    assert_eq!(small.weight(&"D", &"B"), Some(5));
+    assert_eq!(small.weight(&"B", &"E"), None);
+    assert_eq!(
+        small.to_dot("small"),
+        "graph \"small\" {\n    \"B\";\n    \"D\";\n    \"E\";\n    \"B\" -- \"D\" [label=\"5\"];\n}\n"
+    );
+}
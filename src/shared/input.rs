@@ -0,0 +1,208 @@
+/// Loading and cleaning up puzzle inputs.
+///
+/// Inputs saved on other machines come with CRLF line endings, a byte order mark, stray blank
+/// lines at the end or tabs where the puzzle used spaces. `normalize` irons all of that out so the
+/// parsers only ever see `\n`-terminated lines. `load` and `prepare` also check the text against
+/// the layout each day expects, and print a warning (but carry on) when it looks off.
+///
+/// Days that can work line by line use `open` and `stream_lines` instead, so even a huge
+/// generated input never has to fit in memory.
+use std::{
+    fs::{read_to_string, File},
+    io::{BufRead, BufReader},
+};
+
+/// Tabs are expanded to the next multiple of this many columns
+const TAB_WIDTH: usize = 4;
+
+/// Rough shape of a day's input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Layout {
+    /// one item per line, no blank lines in between
+    Lines,
+    /// a single line, e.g. a signal or a list of jets
+    SingleLine,
+    /// every line the same width
+    Grid,
+    /// exactly this many blank-line-separated sections
+    Sections(usize),
+    /// blank-line-separated blocks that are all this many lines long
+    Blocks { lines: usize },
+}
+
+/// What each day's input should look like. Days that don't show up here aren't checked.
+pub(crate) fn layout(day: usize) -> Option<Layout> {
+    match day {
+        2 | 3 | 4 | 7 | 9 | 10 | 14 | 15 | 16 | 18 | 19 | 21 | 25 => Some(Layout::Lines),
+        6 | 17 => Some(Layout::SingleLine),
+        8 | 12 | 23 | 24 => Some(Layout::Grid),
+        5 | 22 => Some(Layout::Sections(2)),
+        11 => Some(Layout::Blocks { lines: 6 }),
+        13 => Some(Layout::Blocks { lines: 2 }),
+        _ => None,
+    }
+}
+
+/// BOM stripped, CRLF and CR turned into LF, tabs expanded, and exactly one newline at the end.
+/// Trailing spaces are kept since some inputs (day05's crate stacks) are column aligned.
+pub(crate) fn normalize(raw: &str) -> String {
+    let text = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let mut out = String::with_capacity(text.len() + 1);
+    for line in text.trim_end_matches('\n').split('\n') {
+        expand_tabs(line, &mut out);
+        out.push('\n');
+    }
+    if out.trim().is_empty() {
+        out.clear();
+    }
+    out
+}
+
+fn expand_tabs(line: &str, out: &mut String) {
+    let mut col = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = TAB_WIDTH - col % TAB_WIDTH;
+            out.extend(std::iter::repeat_n(' ', spaces));
+            col += spaces;
+        } else {
+            out.push(c);
+            col += 1;
+        }
+    }
+}
+
+/// Everything that looks wrong about an already normalized input
+pub(crate) fn check(text: &str, layout: Layout) -> Vec<String> {
+    if text.is_empty() {
+        return vec!["input is empty".to_string()];
+    }
+    let mut warnings = vec![];
+    if let Some((n, _)) = text.lines().enumerate().find(|(_, l)| !l.is_ascii()) {
+        warnings.push(format!("line {} has non-ASCII characters", n + 1));
+    }
+    let blank_line = text.lines().position(str::is_empty).map(|n| n + 1);
+    let sections = super::parse::blocks(text).collect::<Vec<_>>();
+    match layout {
+        Layout::Lines | Layout::Grid => {
+            if let Some(n) = blank_line {
+                warnings.push(format!("unexpected blank line at line {n}"));
+            }
+        }
+        Layout::SingleLine => {
+            let lines = text.lines().count();
+            if lines != 1 {
+                warnings.push(format!("expected a single line, found {lines}"));
+            }
+        }
+        Layout::Sections(expected) => {
+            if sections.len() != expected {
+                warnings.push(format!(
+                    "expected {expected} sections separated by blank lines, found {}",
+                    sections.len()
+                ));
+            }
+        }
+        Layout::Blocks { lines } => {
+            for (start, block) in sections.iter() {
+                let found = block.lines().count();
+                if found != lines {
+                    warnings.push(format!(
+                        "block starting at line {start} has {found} lines, expected {lines}"
+                    ));
+                }
+            }
+        }
+    }
+    if layout == Layout::Grid {
+        let width = text.lines().next().unwrap().len();
+        if let Some((n, l)) = text.lines().enumerate().find(|(_, l)| l.len() != width) {
+            warnings.push(format!(
+                "line {} is {} wide but line 1 is {width}, grid isn't rectangular",
+                n + 1,
+                l.len()
+            ));
+        }
+    }
+    warnings
+}
+
+/// Normalize an input that's already in memory (e.g. from `include_str!`) and warn about
+/// anything unexpected for `day`
+pub(crate) fn prepare(day: usize, raw: &str) -> String {
+    let text = normalize(raw);
+    if let Some(layout) = layout(day) {
+        for warning in check(&text, layout) {
+            eprintln!("warning: day {day:02} input: {warning}");
+        }
+    }
+    text
+}
+
+/// Read `src/dayNN/input.txt` and `prepare` it
+pub(crate) fn load(day: usize) -> String {
+    let path = format!("src/day{day:02}/input.txt");
+    let raw = read_to_string(&path).unwrap_or_else(|e| panic!("couldn't read {path}: {e}"));
+    prepare(day, &raw)
+}
+
+/// Buffered reader over `src/dayNN/input.txt`
+pub(crate) fn open(day: usize) -> BufReader<File> {
+    let path = format!("src/day{day:02}/input.txt");
+    let file = File::open(&path).unwrap_or_else(|e| panic!("couldn't open {path}: {e}"));
+    BufReader::new(file)
+}
+
+/// Lines of a streamed input, cleaned up one at a time like `normalize` does: no BOM, no `\r`,
+/// tabs expanded. Blank lines are passed through, trailing ones included.
+pub(crate) fn stream_lines(reader: impl BufRead) -> impl Iterator<Item = String> {
+    reader.lines().enumerate().map(|(n, line)| {
+        let line = line.unwrap_or_else(|e| panic!("error reading input line {}: {e}", n + 1));
+        let line = match n {
+            0 => line.strip_prefix('\u{feff}').unwrap_or(&line),
+            _ => &line,
+        };
+        let mut out = String::with_capacity(line.len());
+        expand_tabs(line.trim_end_matches('\r'), &mut out);
+        out
+    })
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(
+        normalize("\u{feff}a\r\nb\r\n\r\n\r\nc\rd\n\n\n"),
+        "a\nb\n\n\nc\nd\n"
+    );
+    assert_eq!(normalize("\t[A]\n1\t2\n"), "    [A]\n1   2\n");
+    assert_eq!(normalize("  \n\n"), "");
+    assert_eq!(normalize("no newline"), "no newline\n");
+}
+
+#[test]
+fn test_check() {
+    assert!(check("1\n2\n\n3\n", Layout::Sections(2)).is_empty());
+    assert_eq!(
+        check("1\n2\n", Layout::Sections(2)),
+        vec!["expected 2 sections separated by blank lines, found 1"]
+    );
+    assert_eq!(
+        check("..#\n.#\n", Layout::Grid),
+        vec!["line 2 is 2 wide but line 1 is 3, grid isn't rectangular"]
+    );
+    assert_eq!(
+        check("a\nb\n\nc\n", Layout::Blocks { lines: 2 }),
+        vec!["block starting at line 4 has 1 lines, expected 2"]
+    );
+    assert_eq!(check("", Layout::Lines), vec!["input is empty"]);
+}
+
+#[test]
+fn test_stream_lines() {
+    let raw = "\u{feff}a\r\n\tb\r\n\r\nc";
+    assert_eq!(
+        stream_lines(raw.as_bytes()).collect::<Vec<_>>(),
+        vec!["a", "    b", "", "c"]
+    );
+}
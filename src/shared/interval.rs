@@ -0,0 +1,265 @@
+/// A set of integers stored as sorted, inclusive `(lo, hi)` ranges.
+///
+/// Ranges are kept disjoint and merged whenever they touch, so `[1, 3]` and `[4, 6]` become
+/// `[1, 6]`. That means every gap between two stored ranges holds at least one missing value.
+use std::{
+    fmt::Debug,
+    ops::{Add, Sub},
+};
+
+/// Integer types an `IntervalSet` can hold
+pub(crate) trait Discrete:
+    Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self>
+{
+    const ONE: Self;
+    const ZERO: Self;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {
+        $(impl Discrete for $t {
+            const ONE: Self = 1;
+            const ZERO: Self = 0;
+        })*
+    };
+}
+
+impl_discrete!(i32, i64, isize, u32, u64, usize);
+
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: Discrete> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: vec![] }
+    }
+}
+
+impl<T: Discrete> Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set()
+            .entries(self.ranges.iter().map(|(lo, hi)| lo..=hi))
+            .finish()
+    }
+}
+
+impl<T: Discrete> From<(T, T)> for IntervalSet<T> {
+    fn from((lo, hi): (T, T)) -> Self {
+        let mut set = Self::new();
+        set.insert(lo, hi);
+        set
+    }
+}
+
+impl<T: Discrete> FromIterator<(T, T)> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut ranges: Vec<(T, T)> = iter.into_iter().filter(|(lo, hi)| lo <= hi).collect();
+        ranges.sort_unstable();
+        let mut set = Self::new();
+        for (lo, hi) in ranges {
+            // sorted by start, so only the last range could touch this one
+            match set.ranges.last_mut() {
+                Some(last) if lo <= last.1 || lo - last.1 == T::ONE => last.1 = last.1.max(hi),
+                _ => set.ranges.push((lo, hi)),
+            }
+        }
+        set
+    }
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of disjoint ranges (not the number of values, see `coverage`)
+    pub(crate) fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.ranges.iter().copied()
+    }
+
+    pub(crate) fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.0)
+    }
+
+    pub(crate) fn max(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.1)
+    }
+
+    /// Total number of values in the set
+    pub(crate) fn coverage(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |acc, &(lo, hi)| acc + (hi - lo) + T::ONE)
+    }
+
+    /// Add `lo..=hi`, merging with any ranges it overlaps or touches
+    pub(crate) fn insert(&mut self, mut lo: T, mut hi: T) {
+        if lo > hi {
+            return;
+        }
+        // first range that could touch `lo..=hi`, i.e. one that doesn't end before `lo - 1`
+        let first = self
+            .ranges
+            .partition_point(|&(_, h)| h < lo && lo - h > T::ONE);
+        let mut last = first;
+        while last < self.ranges.len() {
+            let (l, h) = self.ranges[last];
+            if l > hi && l - hi > T::ONE {
+                break;
+            }
+            lo = lo.min(l);
+            hi = hi.max(h);
+            last += 1;
+        }
+        self.ranges.splice(first..last, [(lo, hi)]);
+    }
+
+    /// Remove `lo..=hi` from the set, splitting a range in two if needed
+    pub(crate) fn remove(&mut self, lo: T, hi: T) {
+        if lo > hi {
+            return;
+        }
+        let first = self.ranges.partition_point(|&(_, h)| h < lo);
+        let mut last = first;
+        let mut keep = vec![];
+        while last < self.ranges.len() && self.ranges[last].0 <= hi {
+            let (l, h) = self.ranges[last];
+            if l < lo {
+                keep.push((l, lo - T::ONE));
+            }
+            if h > hi {
+                keep.push((hi + T::ONE, h));
+            }
+            last += 1;
+        }
+        self.ranges.splice(first..last, keep);
+    }
+
+    pub(crate) fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).collect()
+    }
+
+    pub(crate) fn subtract(&self, other: &Self) -> Self {
+        let mut res = self.clone();
+        for (lo, hi) in other.iter() {
+            res.remove(lo, hi);
+        }
+        res
+    }
+
+    pub(crate) fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            let lo = a.0.max(b.0);
+            let hi = a.1.min(b.1);
+            if lo <= hi {
+                ranges.push((lo, hi));
+            }
+            // drop whichever range ends first, it can't overlap anything else
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Only keep the values within `lo..=hi`
+    pub(crate) fn clamp(&self, lo: T, hi: T) -> Self {
+        self.intersection(&Self::from((lo, hi)))
+    }
+
+    pub(crate) fn contains(&self, val: T) -> bool {
+        self.contains_range(val, val)
+    }
+
+    /// Is every value in `lo..=hi` in the set?
+    pub(crate) fn contains_range(&self, lo: T, hi: T) -> bool {
+        let i = self.ranges.partition_point(|&(_, h)| h < lo);
+        self.ranges.get(i).is_some_and(|&(l, h)| l <= lo && hi <= h)
+    }
+
+    /// Is any value in `lo..=hi` in the set?
+    pub(crate) fn overlaps(&self, lo: T, hi: T) -> bool {
+        let i = self.ranges.partition_point(|&(_, h)| h < lo);
+        self.ranges.get(i).is_some_and(|&(l, _)| l <= hi)
+    }
+
+    pub(crate) fn is_superset(&self, other: &Self) -> bool {
+        other.iter().all(|(lo, hi)| self.contains_range(lo, hi))
+    }
+
+    pub(crate) fn is_disjoint(&self, other: &Self) -> bool {
+        other.iter().all(|(lo, hi)| !self.overlaps(lo, hi))
+    }
+
+    /// Missing ranges between the lowest and highest values in the set
+    pub(crate) fn gaps(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.ranges
+            .windows(2)
+            .map(|w| (w[0].1 + T::ONE, w[1].0 - T::ONE))
+    }
+
+    /// Missing ranges within `lo..=hi`, including before the first and after the last range
+    pub(crate) fn gaps_within(&self, lo: T, hi: T) -> impl Iterator<Item = (T, T)> {
+        Self::from((lo, hi)).subtract(self).ranges.into_iter()
+    }
+}
+
+#[test]
+fn test_insert_merges() {
+    let mut set = IntervalSet::new();
+    set.insert(5, 7);
+    set.insert(1, 2);
+    set.insert(10, 12);
+    assert_eq!(set.len(), 3);
+    // touches [1, 2] and overlaps [5, 7]
+    set.insert(3, 5);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![(1, 7), (10, 12)]);
+    assert_eq!(set.coverage(), 10);
+    assert_eq!(
+        set,
+        [(10, 12), (3, 5), (5, 7), (1, 2)].into_iter().collect()
+    );
+}
+
+#[test]
+fn test_remove_and_gaps() {
+    let mut set: IntervalSet<i64> = [(0, 10), (20, 30)].into_iter().collect();
+    set.remove(5, 22);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![(0, 4), (23, 30)]);
+    assert_eq!(set.gaps().collect::<Vec<_>>(), vec![(5, 22)]);
+    assert_eq!(
+        set.gaps_within(-2, 32).collect::<Vec<_>>(),
+        vec![(-2, -1), (5, 22), (31, 32)]
+    );
+    set.remove(2, 2);
+    assert_eq!(set.len(), 3);
+    assert!(!set.contains(2));
+}
+
+#[test]
+fn test_set_queries() {
+    let a: IntervalSet<u32> = [(2, 8)].into_iter().collect();
+    let b: IntervalSet<u32> = [(3, 7)].into_iter().collect();
+    let c: IntervalSet<u32> = [(8, 9)].into_iter().collect();
+    assert!(a.is_superset(&b) && !b.is_superset(&a));
+    assert!(!a.is_disjoint(&c) && b.is_disjoint(&c));
+    assert_eq!(a.intersection(&c), IntervalSet::from((8, 8)));
+    assert_eq!(a.union(&c), IntervalSet::from((2, 9)));
+    assert_eq!(a.subtract(&b).coverage(), 2);
+    assert_eq!(a.clamp(0, 4), IntervalSet::from((2, 4)));
+}
@@ -0,0 +1,195 @@
+/// Reading the block letters some puzzles draw instead of giving a plain answer.
+///
+/// Two fonts turn up: the 4x6 one on 2022's CRT (day 10) and the 6x10 one from 2018's stars.
+/// A screen is lines of `#` (lit) and anything else (dark), with letters starting in the left
+/// column and a fixed number of blank columns between them. Anything that doesn't match a known
+/// letter exactly is an error, so a misread never makes it into an answer.
+use custom_error::custom_error;
+
+custom_error! {pub OcrError
+    UnknownSize{width: usize, height: usize} =
+        "no font is {height} pixels tall, can't read a {width}x{height} screen",
+    Unrecognized{position: usize, glyph: String} = "letter {position} isn't in the font:\n{glyph}"
+}
+
+pub(crate) struct Font {
+    pub width: usize,
+    pub height: usize,
+    /// blank columns between letters
+    pub spacing: usize,
+    pub glyphs: &'static [(char, &'static [&'static str])],
+}
+
+impl Font {
+    fn pitch(&self) -> usize {
+        self.width + self.spacing
+    }
+
+    pub(crate) fn glyph(&self, letter: char) -> Option<&'static [&'static str]> {
+        self.glyphs
+            .iter()
+            .find(|(c, _)| *c == letter)
+            .map(|(_, g)| *g)
+    }
+}
+
+pub(crate) const FONT_4X6: Font = Font {
+    width: 4,
+    height: 6,
+    spacing: 1,
+    glyphs: &[
+        ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+        ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+        ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+        ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+        ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+        ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+        ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+        ('I', &[".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+        ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+        ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+        ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+        ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+        ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+        ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+        ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+        ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+        ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+    ],
+};
+
+#[rustfmt::skip]
+pub(crate) const FONT_6X10: Font = Font {
+    width: 6,
+    height: 10,
+    spacing: 2,
+    glyphs: &[
+        ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+        ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+        ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+        ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+        ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+        ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+        ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+        ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+        ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+        ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+        ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+        ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+        ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+        ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+        ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+    ],
+};
+
+const FONTS: [&Font; 2] = [&FONT_4X6, &FONT_6X10];
+
+/// Read a screen, picking the font by how tall it is
+pub(crate) fn read(screen: &str) -> Result<String, OcrError> {
+    let rows = screen.lines().collect::<Vec<_>>();
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    match FONTS.iter().find(|f| f.height == rows.len()) {
+        Some(font) => read_with(&rows, font),
+        None => Err(OcrError::UnknownSize {
+            width,
+            height: rows.len(),
+        }),
+    }
+}
+
+/// Read a screen in the given font. Blank space after the last letter is fine, but every cell
+/// before that has to be a known letter, including the blank columns after it.
+pub(crate) fn read_with(rows: &[&str], font: &Font) -> Result<String, OcrError> {
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    // off the right edge counts as dark, so the last letter can lose its spacing
+    let cell = |start: usize| {
+        rows.iter()
+            .map(|row| {
+                (start..start + font.pitch())
+                    .map(|x| match row.as_bytes().get(x) {
+                        Some(b'#') => '#',
+                        _ => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+    };
+    let last_lit = (0..width)
+        .rev()
+        .find(|&x| rows.iter().any(|r| r.as_bytes().get(x) == Some(&b'#')));
+
+    let mut text = String::new();
+    let Some(last_lit) = last_lit else {
+        return Ok(text);
+    };
+    for (position, start) in (0..=last_lit).step_by(font.pitch()).enumerate() {
+        let cell = cell(start);
+        let letter = font.glyphs.iter().find(|(_, glyph)| {
+            glyph
+                .iter()
+                .zip(cell.iter())
+                .all(|(g, c)| c.starts_with(g) && !c[g.len()..].contains('#'))
+        });
+        match letter {
+            Some((c, _)) => text.push(*c),
+            None => {
+                return Err(OcrError::Unrecognized {
+                    position: position + 1,
+                    glyph: cell.join("\n"),
+                })
+            }
+        }
+    }
+    Ok(text)
+}
+
+/// Draw `text` the way a puzzle would, the inverse of `read_with`
+#[cfg(test)]
+fn render(text: &str, font: &Font) -> String {
+    let mut screen = String::new();
+    for row in 0..font.height {
+        let line = text
+            .chars()
+            .map(|c| font.glyph(c).unwrap()[row])
+            .collect::<Vec<_>>()
+            .join(&".".repeat(font.spacing));
+        screen.push_str(&line);
+        screen.push('\n');
+    }
+    screen
+}
+
+#[test]
+fn test_read_both_fonts() {
+    for font in FONTS {
+        let alphabet = font.glyphs.iter().map(|(c, _)| *c).collect::<String>();
+        assert_eq!(read(&render(&alphabet, font)).unwrap(), alphabet);
+    }
+    // 2022 day 10's CRT leaves the last letter's spacing off the edge of the screen
+    let crt = render("RFZEKBFA", &FONT_4X6);
+    assert!(crt.lines().all(|l| l.len() == 39));
+    assert_eq!(read(&crt).unwrap(), "RFZEKBFA");
+    assert_eq!(read("....\n".repeat(6).as_str()).unwrap(), "");
+}
+
+#[test]
+fn test_unrecognized_glyphs() {
+    // a stray pixel in the gap between two letters
+    let mut rows = render("HI", &FONT_4X6)
+        .lines()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    rows[2].replace_range(4..5, "#");
+    let screen = rows.join("\n");
+    assert!(matches!(
+        read(&screen),
+        Err(OcrError::Unrecognized { position: 1, .. })
+    ));
+    assert!(matches!(
+        read("#\n#\n#\n"),
+        Err(OcrError::UnknownSize {
+            width: 1,
+            height: 3
+        })
+    ));
+}
@@ -0,0 +1,220 @@
+/// nom building blocks for the input shapes that keep coming back: numbers, comma lists,
+/// coordinates, `key: value` lines, and inputs made of lines or blank-line-separated blocks.
+///
+/// `parse_lines` and `parse_blocks` make sure each piece is consumed completely and turn nom's
+/// errors into a `ParseError` that points at the line and column that went wrong.
+use std::str::FromStr;
+
+use custom_error::custom_error;
+use nom::{
+    bytes::complete::{tag, take_till1},
+    character::complete::{char, digit1, one_of, space0},
+    combinator::{all_consuming, map_res, opt, recognize},
+    error::{Error, ErrorKind},
+    multi::separated_list1,
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
+    Finish, IResult, Parser,
+};
+
+custom_error! {pub ParseError
+    Syntax{line: usize, col: usize, text: String, reason: String} =
+        "line {line}, column {col}: {reason}\n    {text}"
+}
+
+/// Non-negative integer of any type, e.g. `42`
+pub(crate) fn unsigned<T: FromStr>(i: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(i)
+}
+
+/// Integer with an optional sign, e.g. `-7` or `+3`
+pub(crate) fn signed<T: FromStr>(i: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(i)
+}
+
+/// `,` with optional spaces around it
+fn comma(i: &str) -> IResult<&str, char> {
+    delimited(space0, char(','), space0)(i)
+}
+
+/// One or more `item`s separated by commas, e.g. `79, 98` or `1,2,3`
+pub(crate) fn comma_list<'a, O, F>(item: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(comma, item)
+}
+
+/// `x,y` pair of (possibly negative) numbers, e.g. `498,4` or `-2, 15`
+pub(crate) fn coord_pair<T: FromStr>(i: &str) -> IResult<&str, (T, T)> {
+    separated_pair(signed, comma, signed)(i)
+}
+
+/// `label=value`, e.g. `x=-3`
+pub(crate) fn labeled<'a, O, F>(
+    label: &'static str,
+    value: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    preceded(terminated(tag(label), char('=')), value)
+}
+
+/// `x=1, y=-2` style coordinates
+pub(crate) fn xy_pair<T: FromStr>(i: &str) -> IResult<&str, (T, T)> {
+    separated_pair(labeled("x", signed), comma, labeled("y", signed))(i)
+}
+
+/// `key: value`, where the key is everything up to the colon (leading spaces skipped)
+pub(crate) fn key_value<'a, O, F>(value: F) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, O)>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_pair(
+        preceded(space0, take_till1(|c| c == ':' || c == '\n')),
+        tuple((char(':'), space0)),
+        value,
+    )
+}
+
+/// Blank-line-separated chunks of the input, with the (1-based) line number each one starts on
+pub(crate) fn blocks(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut line = 1;
+    input
+        .trim_end_matches('\n')
+        .split("\n\n")
+        .map(move |block| {
+            let start = line;
+            line += block.matches('\n').count() + 2;
+            (start, block)
+        })
+}
+
+/// Parse every line of `input` with `parser`, which has to consume the whole line
+pub(crate) fn parse_lines<'a, O, F>(input: &'a str, mut parser: F) -> Result<Vec<O>, ParseError>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(n, line)| parse_all(line, n + 1, &mut parser))
+        .collect()
+}
+
+/// Parse every blank-line-separated block of `input` with `parser`, which has to consume the
+/// whole block
+pub(crate) fn parse_blocks<'a, O, F>(input: &'a str, mut parser: F) -> Result<Vec<O>, ParseError>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    blocks(input)
+        .map(|(start, block)| parse_all(block, start, &mut parser))
+        .collect()
+}
+
+/// Run `parser` over all of `text`, which starts on line `first_line` of the input. For days
+/// that stream their input a line at a time instead of handing over all of it.
+pub(crate) fn parse_all<'a, O, F>(
+    text: &'a str,
+    first_line: usize,
+    parser: &mut F,
+) -> Result<O, ParseError>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    all_consuming(|i| parser.parse(i))(text)
+        .finish()
+        .map(|(_, out)| out)
+        .map_err(|e| syntax_error(text, first_line, e))
+}
+
+fn syntax_error(text: &str, first_line: usize, err: Error<&str>) -> ParseError {
+    let mut rest = err.input;
+    // leftover lines in a block: point at the start of the first one, not the end of the last
+    if err.code == ErrorKind::Eof {
+        rest = rest.strip_prefix('\n').unwrap_or(rest);
+    }
+    let offset = text.len() - rest.len();
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+    let found = match rest.lines().next() {
+        Some(rest) if !rest.is_empty() => format!("found {:?}", truncate(rest, 16)),
+        _ => "found end of line".to_string(),
+    };
+    ParseError::Syntax {
+        line: first_line + text[..offset].matches('\n').count(),
+        col: offset - line_start + 1,
+        text: text[line_start..line_end].to_string(),
+        reason: format!("{}, {found}", describe(err.code)),
+    }
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((i, _)) => format!("{}...", &s[..i]),
+        None => s.to_string(),
+    }
+}
+
+fn describe(kind: ErrorKind) -> &'static str {
+    match kind {
+        ErrorKind::Digit => "expected a number",
+        ErrorKind::MapRes => "value out of range",
+        ErrorKind::Verify => "value not allowed here",
+        ErrorKind::Tag | ErrorKind::Char | ErrorKind::OneOf => "unexpected text",
+        ErrorKind::Alpha | ErrorKind::AlphaNumeric => "expected a name",
+        ErrorKind::Space | ErrorKind::MultiSpace => "expected whitespace",
+        ErrorKind::Eof => "unexpected trailing input",
+        _ => "couldn't parse",
+    }
+}
+
+#[test]
+fn test_combinators() {
+    assert_eq!(signed::<i32>("-12,"), Ok((",", -12)));
+    assert_eq!(signed::<i64>("+3"), Ok(("", 3)));
+    assert!(unsigned::<u8>("-1").is_err());
+    assert!(unsigned::<u8>("300").is_err());
+    assert_eq!(coord_pair::<i32>("498, -4"), Ok(("", (498, -4))));
+    assert_eq!(xy_pair::<i64>("x=2, y=-18:"), Ok((":", (2, -18))));
+    assert_eq!(
+        comma_list(unsigned::<u64>)("79, 98,1 x"),
+        Ok((" x", vec![79, 98, 1]))
+    );
+    assert_eq!(
+        key_value(comma_list(unsigned::<u32>))("  Starting items: 54, 65"),
+        Ok(("", ("Starting items", vec![54, 65])))
+    );
+}
+
+#[test]
+fn test_lines_and_blocks() {
+    assert_eq!(
+        parse_lines("1,2\n-3,4\n", coord_pair::<i32>).unwrap().len(),
+        2
+    );
+    let err = parse_lines("1,2\n3;4", coord_pair::<i32>).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 2: unexpected text, found \";4\"\n    3;4"
+    );
+
+    let input = "a: 1\nb: 2\n\nc: 3\n\nd: x\n";
+    assert_eq!(
+        blocks(input).map(|(line, _)| line).collect::<Vec<_>>(),
+        vec![1, 4, 6]
+    );
+    let err = parse_blocks(input, key_value(unsigned::<u8>)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 1: unexpected trailing input, found \"b: 2\"\n    b: 2"
+    );
+    let err = parse_blocks("a: 1\n\nc: 3\nd: x", |i| {
+        let kv = |i| key_value(unsigned::<u8>)(i);
+        separated_list1(char('\n'), kv)(i)
+    })
+    .unwrap_err();
+    let ParseError::Syntax { line, col, .. } = err;
+    assert_eq!((line, col), (4, 1));
+}
@@ -0,0 +1,201 @@
+/// Property testing: run a check against lots of randomly generated cases, and when one fails,
+/// shrink it down to something small enough to actually debug.
+///
+/// Mostly meant for differential tests, where a day's fast solution is compared against a slow
+/// but obviously correct one. Cases come from `Rng`, so a failure can always be replayed: the
+/// panic message has the seed, and `PROP_SEED` / `PROP_CASES` override the defaults.
+use std::{
+    fmt::Debug,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use super::rng::Rng;
+
+const DEFAULT_CASES: usize = 1000;
+const DEFAULT_SEED: u64 = 2022;
+/// Give up shrinking after this many successful steps, in case a shrinker never bottoms out
+const MAX_SHRINKS: usize = 1000;
+
+/// Seeds and sizes `parse_generated` tries every generator with
+const GENERATED_SEEDS: u64 = 3;
+const GENERATED_SIZES: [usize; 3] = [1, 2, 5];
+
+/// `Err` explains what went wrong
+pub(crate) type Outcome = Result<(), String>;
+
+/// The usual property for a differential test: both implementations give the same answer
+pub(crate) fn agree<T: PartialEq + Debug>(fast: T, naive: T) -> Outcome {
+    match fast == naive {
+        true => Ok(()),
+        false => Err(format!("fast gave {fast:?}, naive gave {naive:?}")),
+    }
+}
+
+/// Run `property` on random cases from `generate`. On a failure, keep replacing the case with
+/// the first of its `shrink` candidates that still fails, then panic with the smallest one found.
+pub(crate) fn check<T, G, S, P>(name: &str, mut generate: G, shrink: S, property: P)
+where
+    T: Debug,
+    G: FnMut(&mut Rng) -> T,
+    S: Fn(&T) -> Vec<T>,
+    P: Fn(&T) -> Outcome,
+{
+    let seed = env_or("PROP_SEED", DEFAULT_SEED);
+    let cases = env_or("PROP_CASES", DEFAULT_CASES as u64) as usize;
+    let mut rng = Rng::new(seed);
+    for case in 1..=cases {
+        let input = generate(&mut rng);
+        if let Err(err) = property(&input) {
+            let (input, err, shrinks) = minimize(input, err, &shrink, &property);
+            panic!(
+                "property '{name}' failed on case {case} of {cases} (PROP_SEED={seed}), \
+                shrunk {shrinks} times\n  input: {input:?}\n  {err}"
+            );
+        }
+    }
+}
+
+/// Hand inputs from `day`'s `generate`, over a few seeds and sizes, to `parse`, which panics if
+/// one doesn't parse. That panic is then followed by one saying which seed and size it was, so
+/// the input can be replayed with `generate <day> --seed S --size N`.
+pub(crate) fn parse_generated(
+    day: usize,
+    generate: fn(&mut Rng, usize) -> String,
+    mut parse: impl FnMut(&str),
+) {
+    for seed in 0..GENERATED_SEEDS {
+        for size in GENERATED_SIZES {
+            let input = generate(&mut Rng::new(seed), size);
+            if catch_unwind(AssertUnwindSafe(|| parse(&input))).is_err() {
+                panic!("day {day:02}: generated input didn't parse (--seed {seed} --size {size})");
+            }
+        }
+    }
+}
+
+fn minimize<T, S, P>(mut input: T, mut err: String, shrink: &S, property: &P) -> (T, String, usize)
+where
+    S: Fn(&T) -> Vec<T>,
+    P: Fn(&T) -> Outcome,
+{
+    let mut shrinks = 0;
+    'shrinking: while shrinks < MAX_SHRINKS {
+        for smaller in shrink(&input) {
+            if let Err(smaller_err) = property(&smaller) {
+                (input, err) = (smaller, smaller_err);
+                shrinks += 1;
+                continue 'shrinking;
+            }
+        }
+        break;
+    }
+    (input, err, shrinks)
+}
+
+fn env_or(var: &str, default: u64) -> u64 {
+    match std::env::var(var) {
+        Ok(val) => val
+            .parse()
+            .unwrap_or_else(|_| panic!("{var} should be a number, got {val:?}")),
+        Err(_) => default,
+    }
+}
+
+/// For cases that can't get any smaller
+pub(crate) fn no_shrink<T>(_: &T) -> Vec<T> {
+    vec![]
+}
+
+/// Towards zero: zero itself, half way there, then one step closer
+pub(crate) fn shrink_int(val: &i64) -> Vec<i64> {
+    let val = *val;
+    let mut out = vec![];
+    for smaller in [0, val / 2, val - val.signum()] {
+        if smaller != val && !out.contains(&smaller) {
+            out.push(smaller);
+        }
+    }
+    out
+}
+
+/// Drop big chunks first, then single items, then try shrinking each item in place
+pub(crate) fn shrink_vec<T: Clone>(items: &[T], shrink_item: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    let mut out = vec![];
+    let mut chunk = items.len() / 2;
+    while chunk > 0 {
+        for start in (0..items.len()).step_by(chunk) {
+            let mut fewer = items[..start].to_vec();
+            fewer.extend_from_slice(&items[(start + chunk).min(items.len())..]);
+            out.push(fewer);
+        }
+        chunk /= 2;
+    }
+    for (i, item) in items.iter().enumerate() {
+        for smaller in shrink_item(item) {
+            let mut copy = items.to_vec();
+            copy[i] = smaller;
+            out.push(copy);
+        }
+    }
+    out
+}
+
+#[test]
+fn test_passing_property() {
+    check(
+        "reversing twice",
+        |rng| {
+            (0..rng.range(0..=20))
+                .map(|_| rng.range(-9..=9))
+                .collect::<Vec<i64>>()
+        },
+        |v| shrink_vec(v, shrink_int),
+        |v| {
+            let mut twice = v.clone();
+            twice.reverse();
+            twice.reverse();
+            agree(&twice, v)
+        },
+    );
+}
+
+#[test]
+fn test_shrinks_to_minimal_case() {
+    // "nothing is over 10" is false, and the smallest counterexample is a single 11
+    let all_small = |v: &Vec<i64>| match v.iter().find(|&&x| x > 10) {
+        Some(x) => Err(format!("{x} is too big")),
+        None => Ok(()),
+    };
+    let (shrunk, err, _) = minimize(
+        vec![3, 97, -2, 7, 40],
+        String::new(),
+        &|v: &Vec<i64>| shrink_vec(v, shrink_int),
+        &all_small,
+    );
+    assert_eq!(shrunk, vec![11]);
+    assert_eq!(err, "11 is too big");
+
+    let result = std::panic::catch_unwind(|| {
+        check(
+            "all small",
+            |rng| (0..5).map(|_| rng.range(0..=50)).collect(),
+            |v| shrink_vec(v, shrink_int),
+            all_small,
+        )
+    });
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("input: [11]"), "{message}");
+}
+
+#[test]
+fn test_parse_generated_names_the_failing_input() {
+    let result = catch_unwind(|| {
+        parse_generated(
+            20,
+            |_, size| size.to_string(),
+            |input| assert_ne!(input, "2"),
+        )
+    });
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("(--seed 0 --size 2)"), "{message}");
+}
@@ -0,0 +1,114 @@
+/// Small, seedable pseudo random number generator (SplitMix64).
+///
+/// Not remotely cryptographic, but fast, and the same seed always gives the same numbers on every
+/// platform, which is all the input generators and property tests need.
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+/// Integer types `Rng::range` can produce
+pub(crate) trait Sample: Copy + PartialOrd {
+    fn to_u64(self) -> u64;
+    fn from_u64(val: u64) -> Self;
+}
+
+macro_rules! impl_sample {
+    ($($t:ty),*) => {
+        $(impl Sample for $t {
+            // going through i64 keeps the offsets right for negative values
+            fn to_u64(self) -> u64 {
+                self as i64 as u64
+            }
+            fn from_u64(val: u64) -> Self {
+                val as i64 as $t
+            }
+        })*
+    };
+}
+
+impl_sample!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "can't pick from an empty range");
+        // reject the top sliver that would make lower values more likely
+        let zone = u64::MAX - (u64::MAX % n);
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+
+    /// Uniform in `lo..=hi`
+    pub(crate) fn range<T: Sample>(&mut self, range: RangeInclusive<T>) -> T {
+        let (lo, hi) = (*range.start(), *range.end());
+        assert!(lo <= hi, "empty range");
+        let span = hi.to_u64().wrapping_sub(lo.to_u64());
+        let offset = match span.checked_add(1) {
+            Some(n) => self.below(n),
+            None => self.next_u64(),
+        };
+        T::from_u64(lo.to_u64().wrapping_add(offset))
+    }
+
+    /// Random index into something `len` long
+    pub(crate) fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// True with probability `p`
+    pub(crate) fn chance(&mut self, p: f64) -> bool {
+        // 53 random bits, as many as an f64 can hold exactly
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub(crate) fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    /// Fisher-Yates
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.index(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[test]
+fn test_rng() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    assert!((0..100).all(|_| a.next_u64() == b.next_u64()));
+    assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+
+    let mut rng = Rng::new(7);
+    let rolls = (0..1000).map(|_| rng.range(-3..=3_i32)).collect::<Vec<_>>();
+    assert!(rolls.iter().all(|r| (-3..=3).contains(r)));
+    assert!((-3..=3).all(|v| rolls.contains(&v)));
+    assert_eq!(rng.range(5..=5_u8), 5);
+    let _ = rng.range(i64::MIN..=i64::MAX);
+
+    let mut items = (0..10).collect::<Vec<_>>();
+    rng.shuffle(&mut items);
+    items.sort();
+    assert_eq!(items, (0..10).collect::<Vec<_>>());
+}
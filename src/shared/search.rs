@@ -0,0 +1,261 @@
+/// Generic graph searches: BFS, Dijkstra and A*.
+///
+/// Nodes can be anything `Eq + Hash + Clone`, and the graph is never built up front. Each search
+/// just takes a `successors` closure that yields the neighbors of a node (plus the move cost for
+/// the weighted searches). Every search accepts multiple starting nodes and stops as soon as a
+/// node satisfying `is_goal` is popped.
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+pub(crate) mod optimize;
+pub(crate) mod timed;
+
+/// A path found by one of the searches. `nodes` runs from a start node to the goal (inclusive).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+impl<N, C> Path<N, C> {
+    pub(crate) fn start(&self) -> &N {
+        self.nodes.first().unwrap()
+    }
+
+    pub(crate) fn goal(&self) -> &N {
+        self.nodes.last().unwrap()
+    }
+
+    /// Number of moves taken (not the number of nodes)
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+}
+
+/// Walk the parent pointers back from `end` to whichever start it was reached from
+fn backtrace<N>(parent: &HashMap<N, Option<N>>, end: N) -> Vec<N>
+where
+    N: Eq + Hash + Clone,
+{
+    let mut path = vec![end];
+    while let Some(Some(prev)) = parent.get(path.last().unwrap()) {
+        path.push(prev.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Unweighted shortest path from any of `starts` to the first node matching `is_goal`.
+pub(crate) fn bfs<N, FN, IN, FG>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+    mut is_goal: FG,
+) -> Option<Path<N, usize>>
+where
+    N: Eq + Hash + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+{
+    let mut parent: HashMap<N, Option<N>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(e) = parent.entry(start.clone()) {
+            e.insert(None);
+            queue.push_back(start);
+        }
+    }
+    while let Some(curr) = queue.pop_front() {
+        if is_goal(&curr) {
+            let nodes = backtrace(&parent, curr);
+            return Some(Path {
+                cost: nodes.len() - 1,
+                nodes,
+            });
+        }
+        for nbr in successors(&curr) {
+            if let Entry::Vacant(e) = parent.entry(nbr.clone()) {
+                e.insert(Some(curr.clone()));
+                queue.push_back(nbr);
+            }
+        }
+    }
+    None
+}
+
+/// Unweighted distance from the nearest of `starts` to every reachable node.
+pub(crate) fn bfs_distances<N, FN, IN>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+) -> HashMap<N, usize>
+where
+    N: Eq + Hash + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+{
+    let mut dist = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(e) = dist.entry(start.clone()) {
+            e.insert(0);
+            queue.push_back(start);
+        }
+    }
+    while let Some(curr) = queue.pop_front() {
+        let d = dist[&curr];
+        for nbr in successors(&curr) {
+            if let Entry::Vacant(e) = dist.entry(nbr.clone()) {
+                e.insert(d + 1);
+                queue.push_back(nbr);
+            }
+        }
+    }
+    dist
+}
+
+/// Lowest-cost path from any of `starts` to the first node matching `is_goal`.
+/// `successors` yields `(neighbor, move_cost)` pairs.
+pub(crate) fn dijkstra<N, C, FN, IN, FG>(
+    starts: impl IntoIterator<Item = N>,
+    successors: FN,
+    is_goal: FG,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FG: FnMut(&N) -> bool,
+{
+    astar(starts, successors, |_| C::default(), is_goal)
+}
+
+/// Dijkstra guided by `heuristic`, an estimate of the remaining cost to a goal.
+/// The heuristic must never overestimate, or the path found may not be the cheapest.
+pub(crate) fn astar<N, C, FN, IN, FH, FG>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut is_goal: FG,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FG: FnMut(&N) -> bool,
+{
+    // Nodes are interned so the heap only needs to order (cost, index) pairs,
+    // no custom `Ord` on the node type required.
+    let mut nodes: Vec<N> = vec![];
+    let mut index: HashMap<N, usize> = HashMap::new();
+    let mut parent: Vec<Option<usize>> = vec![];
+    let mut best: Vec<C> = vec![];
+    // lowest (estimated total, cost so far) gets popped first
+    let mut pq = BinaryHeap::new();
+
+    for start in starts {
+        if index.contains_key(&start) {
+            continue;
+        }
+        let i = nodes.len();
+        index.insert(start.clone(), i);
+        parent.push(None);
+        best.push(C::default());
+        pq.push(Reverse((heuristic(&start), C::default(), i)));
+        nodes.push(start);
+    }
+
+    while let Some(Reverse((_, cost, i))) = pq.pop() {
+        // stale entry, a cheaper way here was already found
+        if cost > best[i] {
+            continue;
+        }
+        let curr = nodes[i].clone();
+        if is_goal(&curr) {
+            let mut path = vec![curr];
+            let mut p = parent[i];
+            while let Some(j) = p {
+                path.push(nodes[j].clone());
+                p = parent[j];
+            }
+            path.reverse();
+            return Some(Path { nodes: path, cost });
+        }
+        for (nbr, move_cost) in successors(&curr) {
+            let new_cost = cost + move_cost;
+            let j = match index.get(&nbr) {
+                Some(&j) => {
+                    if new_cost >= best[j] {
+                        continue;
+                    }
+                    best[j] = new_cost;
+                    parent[j] = Some(i);
+                    j
+                }
+                None => {
+                    let j = nodes.len();
+                    index.insert(nbr.clone(), j);
+                    parent.push(Some(i));
+                    best.push(new_cost);
+                    nodes.push(nbr);
+                    j
+                }
+            };
+            let estimate = new_cost + heuristic(&nodes[j]);
+            pq.push(Reverse((estimate, new_cost, j)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+/// 5x5 open grid with a wall down column 2, except a gap at the bottom
+fn grid_successors(&(x, y): &(i32, i32)) -> Vec<(i32, i32)> {
+    [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .iter()
+        .map(|(dx, dy)| (x + dx, y + dy))
+        .filter(|&(nx, ny)| (0..5).contains(&nx) && (0..5).contains(&ny))
+        .filter(|&(nx, ny)| nx != 2 || ny == 4)
+        .collect()
+}
+
+#[test]
+fn test_bfs_path() {
+    let path = bfs([(0, 0)], grid_successors, |&n| n == (4, 0)).unwrap();
+    assert_eq!(path.len(), 12);
+    assert_eq!(*path.start(), (0, 0));
+    assert_eq!(*path.goal(), (4, 0));
+    assert!(bfs([(0, 0)], grid_successors, |&n| n == (2, 0)).is_none());
+}
+
+#[test]
+fn test_multi_source() {
+    let path = bfs([(0, 0), (3, 3)], grid_successors, |&n| n == (4, 0)).unwrap();
+    assert_eq!(*path.start(), (3, 3));
+    assert_eq!(path.len(), 4);
+    let dist = bfs_distances([(0, 0)], grid_successors);
+    assert_eq!(dist[&(4, 0)], 12);
+    assert_eq!(dist.len(), 21);
+}
+
+#[test]
+fn test_weighted_searches_agree() {
+    // moving down is expensive
+    let weighted = |n: &(i32, i32)| {
+        grid_successors(n)
+            .into_iter()
+            .map(|m| (m, if m.1 > n.1 { 3 } else { 1 }))
+            .collect::<Vec<_>>()
+    };
+    let manhattan = |&(x, y): &(i32, i32)| (4 - x).abs() + y.abs();
+    let d = dijkstra([(0, 0)], weighted, |&n| n == (4, 0)).unwrap();
+    let a = astar([(0, 0)], weighted, manhattan, |&n| n == (4, 0)).unwrap();
+    assert_eq!(d.cost, 20);
+    assert_eq!(a.cost, d.cost);
+}
@@ -0,0 +1,186 @@
+/// Depth-first branch-and-bound for "what's the best score reachable from here" puzzles.
+///
+/// A puzzle implements `Problem`: how to branch from a state, what a state is worth if we stopped
+/// there, an optimistic bound on the best it could still reach, and optionally a dominance key.
+/// `maximize` walks the state space depth first and prunes with two rules:
+///
+/// - bound: the state can't beat the best value found so far, even optimistically
+/// - dominance: a state with the same key and at least as good a score was already explored
+///
+/// The dominance table doubles as a memo, so keying on the whole state turns this into a plain
+/// memoized DFS. A time budget can stop the search early, the result then says so.
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+pub(crate) trait Problem {
+    type State;
+    /// States with equal keys are compared by their dominance score
+    type Key: Eq + Hash;
+
+    fn successors(&self, state: &Self::State) -> Vec<Self::State>;
+
+    /// Score if the search stopped at this state
+    fn value(&self, state: &Self::State) -> i64;
+
+    /// Nothing reachable from `state` may score higher than this
+    fn upper_bound(&self, _state: &Self::State) -> i64 {
+        i64::MAX
+    }
+
+    /// `(key, score)`. A state is pruned if a state with the same key and a score at least as
+    /// high was explored before.
+    fn dominance(&self, _state: &Self::State) -> Option<(Self::Key, i64)> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Stats {
+    /// states popped and expanded
+    pub explored: usize,
+    pub pruned_by_bound: usize,
+    pub pruned_by_dominance: usize,
+    /// longest chain of states from the start
+    pub max_depth: usize,
+    /// the time budget ran out, `best` might not be optimal
+    pub timed_out: bool,
+    pub elapsed: Duration,
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "explored {}, pruned {} by bound and {} by dominance, max depth {}, took {:?}{}",
+            self.explored,
+            self.pruned_by_bound,
+            self.pruned_by_dominance,
+            self.max_depth,
+            self.elapsed,
+            if self.timed_out { " (timed out)" } else { "" }
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Outcome<S> {
+    pub value: i64,
+    /// the state that scored `value`
+    pub best: S,
+    pub stats: Stats,
+}
+
+/// How often (in states popped, pruned or not) to check the clock
+const CLOCK_INTERVAL: usize = 1024;
+
+/// Best value reachable from `start`, searching depth first
+pub(crate) fn maximize<P>(
+    problem: &P,
+    start: P::State,
+    budget: Option<Duration>,
+) -> Outcome<P::State>
+where
+    P: Problem,
+    P::State: Clone,
+{
+    let timer = Instant::now();
+    let mut stats = Stats::default();
+    let mut dominance: HashMap<P::Key, i64> = HashMap::new();
+    let mut value = problem.value(&start);
+    let mut best = start.clone();
+    let mut stack = vec![(start, 0)];
+    let mut popped = 0_usize;
+
+    while let Some((state, depth)) = stack.pop() {
+        if popped.is_multiple_of(CLOCK_INTERVAL)
+            && budget.is_some_and(|budget| timer.elapsed() > budget)
+        {
+            stats.timed_out = true;
+            break;
+        }
+        popped += 1;
+        if problem.upper_bound(&state) <= value {
+            stats.pruned_by_bound += 1;
+            continue;
+        }
+        if let Some((key, score)) = problem.dominance(&state) {
+            match dominance.get(&key) {
+                Some(&seen) if seen >= score => {
+                    stats.pruned_by_dominance += 1;
+                    continue;
+                }
+                _ => {
+                    dominance.insert(key, score);
+                }
+            }
+        }
+        stats.explored += 1;
+        stats.max_depth = stats.max_depth.max(depth);
+
+        for next in problem.successors(&state) {
+            let next_value = problem.value(&next);
+            if next_value > value {
+                value = next_value;
+                best = next.clone();
+            }
+            stack.push((next, depth + 1));
+        }
+    }
+    stats.elapsed = timer.elapsed();
+    Outcome { value, best, stats }
+}
+
+#[cfg(test)]
+/// 0/1 knapsack: state is (next item, weight used, value so far)
+struct Knapsack {
+    items: Vec<(i64, i64)>,
+    capacity: i64,
+}
+
+#[cfg(test)]
+impl Problem for Knapsack {
+    type State = (usize, i64, i64);
+    type Key = (usize, i64);
+
+    fn successors(&self, &(i, weight, value): &Self::State) -> Vec<Self::State> {
+        let Some(&(w, v)) = self.items.get(i) else {
+            return vec![];
+        };
+        let mut next = vec![(i + 1, weight, value)];
+        if weight + w <= self.capacity {
+            next.push((i + 1, weight + w, value + v));
+        }
+        next
+    }
+
+    fn value(&self, state: &Self::State) -> i64 {
+        state.2
+    }
+
+    fn upper_bound(&self, &(i, _, value): &Self::State) -> i64 {
+        value + self.items[i..].iter().map(|(_, v)| v).sum::<i64>()
+    }
+
+    fn dominance(&self, &(i, weight, value): &Self::State) -> Option<(Self::Key, i64)> {
+        Some(((i, weight), value))
+    }
+}
+
+#[test]
+fn test_knapsack() {
+    let problem = Knapsack {
+        items: vec![(12, 4), (2, 2), (1, 1), (1, 2), (4, 10)],
+        capacity: 15,
+    };
+    let outcome = maximize(&problem, (0, 0, 0), None);
+    assert_eq!(outcome.value, 15);
+    assert_eq!(outcome.best.1, 8);
+    assert!(!outcome.stats.timed_out);
+    assert!(outcome.stats.pruned_by_bound > 0);
+
+    let timed_out = maximize(&problem, (0, 0, 0), Some(Duration::ZERO));
+    assert!(timed_out.stats.timed_out);
+}
@@ -0,0 +1,167 @@
+/// Search over `(position, time)` states, for mazes whose walls move.
+///
+/// The obstacles are described by an oracle `is_open(pos, time)` that repeats every `period`
+/// steps, which keeps the state space finite: two visits to the same position at the same point
+/// in the cycle are interchangeable. Anything that steps a whole map forward at once (blizzards,
+/// game of life, ...) can be turned into such an oracle with `Frames::record`.
+use std::{collections::HashSet, hash::Hash};
+
+use super::{bfs, Path};
+
+/// Every state of a periodic simulation, one per time step of a full cycle
+#[derive(Debug, Clone)]
+pub(crate) struct Frames<S> {
+    frames: Vec<S>,
+}
+
+impl<S> Frames<S>
+where
+    S: Clone + Eq + Hash,
+{
+    /// Keep calling `step` until the simulation comes back around to `initial`.
+    ///
+    /// Panics if the simulation settles into a cycle that doesn't include `initial`,
+    /// since then the obstacles aren't periodic from time 0.
+    pub(crate) fn record<F>(initial: S, mut step: F) -> Self
+    where
+        F: FnMut(&S) -> S,
+    {
+        let mut seen = HashSet::new();
+        seen.insert(initial.clone());
+        let mut frames = vec![initial];
+        loop {
+            let next = step(frames.last().unwrap());
+            if next == frames[0] {
+                break;
+            }
+            assert!(
+                seen.insert(next.clone()),
+                "simulation cycles back to step {} instead of the initial state",
+                frames.iter().position(|f| *f == next).unwrap()
+            );
+            frames.push(next);
+        }
+        Self { frames }
+    }
+
+    pub(crate) fn period(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// State of the simulation at any time
+    pub(crate) fn at(&self, time: usize) -> &S {
+        &self.frames[time % self.frames.len()]
+    }
+}
+
+/// Fastest way from `start` (leaving at `start_time`) to `goal`.
+///
+/// `moves` yields the candidate positions one step away from a position; include the position
+/// itself if waiting in place is allowed. `is_open` is only ever asked about times already reduced
+/// modulo `period`. The returned path's nodes are `(position, absolute time)` pairs and its cost is
+/// the time taken.
+pub(crate) fn timed_bfs<P, FM, IM, FO>(
+    start: P,
+    start_time: usize,
+    goal: &P,
+    period: usize,
+    mut moves: FM,
+    mut is_open: FO,
+) -> Option<Path<(P, usize), usize>>
+where
+    P: Eq + Hash + Clone,
+    FM: FnMut(&P) -> IM,
+    IM: IntoIterator<Item = P>,
+    FO: FnMut(&P, usize) -> bool,
+{
+    assert!(period > 0, "obstacle period must be at least 1");
+    let path = bfs(
+        [(start, start_time % period)],
+        |(pos, phase)| {
+            let next_phase = (phase + 1) % period;
+            moves(pos)
+                .into_iter()
+                .filter(|next| is_open(next, next_phase))
+                .map(|next| (next, next_phase))
+                .collect::<Vec<_>>()
+        },
+        |(pos, _)| pos == goal,
+    )?;
+    // BFS takes exactly one time step per move, so the absolute time is just the path index
+    Some(Path {
+        cost: path.cost,
+        nodes: path
+            .nodes
+            .into_iter()
+            .enumerate()
+            .map(|(i, (pos, _))| (pos, start_time + i))
+            .collect(),
+    })
+}
+
+/// Visit each of `waypoints` in order, starting at the first one at `start_time`.
+/// Each leg departs as soon as the previous one arrives. Returns one path per leg, or `None` if
+/// any leg is impossible.
+pub(crate) fn timed_route<P, FM, IM, FO>(
+    waypoints: &[P],
+    start_time: usize,
+    period: usize,
+    mut moves: FM,
+    mut is_open: FO,
+) -> Option<Vec<Path<(P, usize), usize>>>
+where
+    P: Eq + Hash + Clone,
+    FM: FnMut(&P) -> IM,
+    IM: IntoIterator<Item = P>,
+    FO: FnMut(&P, usize) -> bool,
+{
+    let mut legs = vec![];
+    let mut time = start_time;
+    for leg in waypoints.windows(2) {
+        let path = timed_bfs(
+            leg[0].clone(),
+            time,
+            &leg[1],
+            period,
+            &mut moves,
+            &mut is_open,
+        )?;
+        time = path.goal().1;
+        legs.push(path);
+    }
+    Some(legs)
+}
+
+#[cfg(test)]
+/// A 1 x 5 corridor with a single door at x == 2 that is only open every third step
+fn corridor_moves(&x: &i32) -> Vec<i32> {
+    [x - 1, x, x + 1]
+        .into_iter()
+        .filter(|nx| (0..5).contains(nx))
+        .collect()
+}
+
+#[test]
+fn test_timed_bfs_waits_for_door() {
+    let path = timed_bfs(0, 0, &4, 3, corridor_moves, |&x, t| x != 2 || t == 0).unwrap();
+    // step to x=1 (t=1), wait (t=2), through the door at t=3, then 2 more steps
+    assert_eq!(path.cost, 5);
+    assert_eq!(path.nodes[3], (2, 3));
+    assert!(timed_bfs(0, 0, &4, 3, corridor_moves, |&x, _| x != 2).is_none());
+}
+
+#[test]
+fn test_timed_route_and_frames() {
+    // door state as a tiny automaton that toggles between 3 states
+    let frames = Frames::record(0_u8, |s| (s + 1) % 3);
+    assert_eq!(frames.period(), 3);
+    let legs = timed_route(&[0, 4, 0], 0, frames.period(), corridor_moves, |&x, t| {
+        x != 2 || *frames.at(t) == 0
+    })
+    .unwrap();
+    assert_eq!(legs.len(), 2);
+    assert_eq!(legs[0].goal(), &(4, 5));
+    assert_eq!(legs[1].start(), &(4, 5));
+    // leave 4 at t=5, the door is next open at t=9, then 2 more steps
+    assert_eq!(legs[1].goal(), &(0, 11));
+}
@@ -0,0 +1,97 @@
+/// Unbounded 2D grid that only stores the cells that have been set.
+///
+/// Handy when things wander off in every direction (elves, sand, rope knots) and a dense
+/// `Grid` would need its size guessed up front.
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SparseGrid<T> {
+    cells: HashMap<(isize, isize), T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> FromIterator<((isize, isize), T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = ((isize, isize), T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub(crate) fn get(&self, pos: (isize, isize)) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub(crate) fn get_mut(&mut self, pos: (isize, isize)) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub(crate) fn contains(&self, pos: (isize, isize)) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    /// Returns the previous value, if there was one
+    pub(crate) fn insert(&mut self, pos: (isize, isize), val: T) -> Option<T> {
+        self.cells.insert(pos, val)
+    }
+
+    pub(crate) fn remove(&mut self, pos: (isize, isize)) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    /// Stored positions, in no particular order
+    pub(crate) fn positions(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        self.cells.iter().map(|(pos, v)| (*pos, v))
+    }
+
+    /// Corners `(min, max)` of the smallest box containing every stored cell
+    pub(crate) fn bounds(&self) -> Option<((isize, isize), (isize, isize))> {
+        self.positions().fold(None, |bounds, (x, y)| match bounds {
+            None => Some(((x, y), (x, y))),
+            Some((min, max)) => Some(((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y)))),
+        })
+    }
+
+    /// Positions around `pos`, whether or not anything is stored there
+    pub(crate) fn adjacent(pos: (isize, isize), diag: bool) -> Vec<(isize, isize)> {
+        let mut direction_diffs = vec![(-1, 0), (0, 1), (1, 0), (0, -1)];
+        if diag {
+            direction_diffs.extend_from_slice(&[(-1, 1), (1, 1), (1, -1), (-1, -1)]);
+        }
+        direction_diffs
+            .into_iter()
+            .map(|(dx, dy)| (pos.0 + dx, pos.1 + dy))
+            .collect()
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// Grid with `val` at each of `points`
+    pub(crate) fn from_points(points: impl IntoIterator<Item = (isize, isize)>, val: T) -> Self {
+        points.into_iter().map(|pos| (pos, val.clone())).collect()
+    }
+}
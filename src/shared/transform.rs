@@ -0,0 +1,368 @@
+/// Geometric transforms for `Grid`: rotations, transpose, flips and cropping.
+///
+/// Every transform is available as a zero-copy `SubGrid` view (which just remaps coordinates
+/// back into the borrowed grid), or as an owned `Grid` via `SubGrid::to_grid`.
+use super::{Grid, GridCoord};
+
+/// One of the 8 symmetries of a rectangle, stored as "flip the view axes, then (maybe) swap them".
+///
+/// Any sequence of rotations/flips/transposes collapses down to one of these, so stacking
+/// transforms on a view never costs more than a single coordinate remap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub(crate) struct Orientation {
+    pub transpose: bool,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+impl Orientation {
+    pub(crate) fn flip_horizontal(self) -> Self {
+        Self {
+            flip_x: !self.flip_x,
+            ..self
+        }
+    }
+
+    pub(crate) fn flip_vertical(self) -> Self {
+        Self {
+            flip_y: !self.flip_y,
+            ..self
+        }
+    }
+
+    pub(crate) fn transpose(self) -> Self {
+        Self {
+            transpose: !self.transpose,
+            flip_x: self.flip_y,
+            flip_y: self.flip_x,
+        }
+    }
+
+    /// Clockwise rotation in quarter turns (negative values rotate counter-clockwise)
+    pub(crate) fn rotate(self, quarter_turns: isize) -> Self {
+        match quarter_turns.rem_euclid(4) {
+            0 => self,
+            1 => self.flip_vertical().transpose(),
+            2 => self.flip_horizontal().flip_vertical(),
+            3 => self.flip_horizontal().transpose(),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Dimensions of the view for a source window of `width` x `height`
+    fn view_dims(&self, width: usize, height: usize) -> (usize, usize) {
+        if self.transpose {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Map a coord in the view back to a coord in the (un-transformed) source window
+    fn to_source(self, coord: GridCoord, view_w: usize, view_h: usize) -> GridCoord {
+        let x = if self.flip_x {
+            view_w - 1 - coord.x
+        } else {
+            coord.x
+        };
+        let y = if self.flip_y {
+            view_h - 1 - coord.y
+        } else {
+            coord.y
+        };
+        if self.transpose {
+            (y, x).into()
+        } else {
+            (x, y).into()
+        }
+    }
+}
+
+/// Borrowed, rectangular window into a `Grid`, optionally rotated/flipped.
+///
+/// Coordinates passed to a `SubGrid` are relative to the view's top-left corner.
+#[derive(Debug)]
+pub(crate) struct SubGrid<'a, T> {
+    grid: &'a Grid<T>,
+    origin: GridCoord,
+    src_width: usize,
+    src_height: usize,
+    orientation: Orientation,
+}
+
+// derive would require `T: Copy`, but we only hold a reference
+impl<'a, T> Clone for SubGrid<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for SubGrid<'a, T> {}
+
+impl<'a, T> SubGrid<'a, T> {
+    pub(crate) fn width(&self) -> usize {
+        self.orientation
+            .view_dims(self.src_width, self.src_height)
+            .0
+    }
+
+    pub(crate) fn height(&self) -> usize {
+        self.orientation
+            .view_dims(self.src_width, self.src_height)
+            .1
+    }
+
+    pub(crate) fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn in_bounds<C>(&self, coord: C) -> bool
+    where
+        C: Into<GridCoord>,
+    {
+        let c = coord.into();
+        c.x < self.width() && c.y < self.height()
+    }
+
+    /// Translate a view coord into the coord of the underlying `Grid`
+    pub(crate) fn grid_coord(&self, coord: GridCoord) -> Option<GridCoord> {
+        if !self.in_bounds(coord) {
+            return None;
+        }
+        let src = self
+            .orientation
+            .to_source(coord, self.width(), self.height());
+        Some(self.origin + src)
+    }
+
+    pub(crate) fn cell(&self, coord: GridCoord) -> Option<&'a T> {
+        let c = self.grid_coord(coord)?;
+        Some(&self.grid.data[c.y * self.grid.width + c.x])
+    }
+
+    /// Row-major iterator over `(view coord, value)`
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (GridCoord, &'a T)> + '_ {
+        let (w, h) = (self.width(), self.height());
+        (0..h)
+            .flat_map(move |y| (0..w).map(move |x| GridCoord { x, y }))
+            .map(|c| (c, self.cell(c).unwrap()))
+    }
+
+    fn with_orientation(self, orientation: Orientation) -> Self {
+        Self {
+            orientation,
+            ..self
+        }
+    }
+
+    /// Rotate clockwise by `degrees` (must be a multiple of 90, negative is counter-clockwise)
+    pub(crate) fn rotate(self, degrees: isize) -> Self {
+        assert!(
+            degrees % 90 == 0,
+            "can only rotate by multiples of 90 degrees"
+        );
+        self.with_orientation(self.orientation.rotate(degrees / 90))
+    }
+
+    pub(crate) fn rotate_cw(self) -> Self {
+        self.rotate(90)
+    }
+
+    pub(crate) fn rotate_ccw(self) -> Self {
+        self.rotate(-90)
+    }
+
+    pub(crate) fn transpose(self) -> Self {
+        self.with_orientation(self.orientation.transpose())
+    }
+
+    /// Mirror left <-> right
+    pub(crate) fn flip_horizontal(self) -> Self {
+        self.with_orientation(self.orientation.flip_horizontal())
+    }
+
+    /// Mirror top <-> bottom
+    pub(crate) fn flip_vertical(self) -> Self {
+        self.with_orientation(self.orientation.flip_vertical())
+    }
+
+    /// Narrow the view to `width` x `height` cells starting at `origin` (in view coords)
+    pub(crate) fn sub_grid(self, origin: GridCoord, width: usize, height: usize) -> Self {
+        assert!(
+            origin.x + width <= self.width() && origin.y + height <= self.height(),
+            "sub grid out of bounds"
+        );
+        if width == 0 || height == 0 {
+            return Self {
+                src_width: 0,
+                src_height: 0,
+                ..self
+            };
+        }
+        // the two opposite corners of the window, in source space
+        let a = self.grid_coord(origin).unwrap();
+        let b = self
+            .grid_coord((origin.x + width - 1, origin.y + height - 1).into())
+            .unwrap();
+        let min: GridCoord = (a.x.min(b.x), a.y.min(b.y)).into();
+        let max: GridCoord = (a.x.max(b.x), a.y.max(b.y)).into();
+        Self {
+            origin: min,
+            src_width: max.x - min.x + 1,
+            src_height: max.y - min.y + 1,
+            ..self
+        }
+    }
+}
+
+impl<'a, T: Clone> SubGrid<'a, T> {
+    /// Copy the view out into an owned `Grid`
+    pub(crate) fn to_grid(self) -> Grid<T> {
+        Grid {
+            width: self.width(),
+            height: self.height(),
+            data: self.iter().map(|(_, v)| v.clone()).collect(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Zero-copy view of the whole grid
+    pub(crate) fn view(&self) -> SubGrid<'_, T> {
+        SubGrid {
+            grid: self,
+            origin: (0, 0).into(),
+            src_width: self.width,
+            src_height: self.height,
+            orientation: Orientation::default(),
+        }
+    }
+
+    /// Zero-copy view of the inclusive box `min..=max`
+    pub(crate) fn sub_grid(&self, min: GridCoord, max: GridCoord) -> SubGrid<'_, T> {
+        assert!(min.x <= max.x && min.y <= max.y);
+        self.view()
+            .sub_grid(min, max.x - min.x + 1, max.y - min.y + 1)
+    }
+
+    /// Smallest inclusive box `(min, max)` containing every cell matching `pred`
+    pub(crate) fn bounding_box<F>(&self, pred: F) -> Option<(GridCoord, GridCoord)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| pred(v))
+            .map(|(i, _)| GridCoord::from((i % self.width, i / self.width)))
+            .fold(None, |bounds, c| match bounds {
+                None => Some((c, c)),
+                Some((min, max)) => Some((
+                    (min.x.min(c.x), min.y.min(c.y)).into(),
+                    (max.x.max(c.x), max.y.max(c.y)).into(),
+                )),
+            })
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub(crate) fn rotated(&self, degrees: isize) -> Grid<T> {
+        self.view().rotate(degrees).to_grid()
+    }
+
+    pub(crate) fn rotated_cw(&self) -> Grid<T> {
+        self.rotated(90)
+    }
+
+    pub(crate) fn rotated_ccw(&self) -> Grid<T> {
+        self.rotated(-90)
+    }
+
+    pub(crate) fn transposed(&self) -> Grid<T> {
+        self.view().transpose().to_grid()
+    }
+
+    pub(crate) fn flipped_horizontal(&self) -> Grid<T> {
+        self.view().flip_horizontal().to_grid()
+    }
+
+    pub(crate) fn flipped_vertical(&self) -> Grid<T> {
+        self.view().flip_vertical().to_grid()
+    }
+
+    /// Owned copy of the inclusive box `min..=max`
+    pub(crate) fn crop(&self, min: GridCoord, max: GridCoord) -> Grid<T> {
+        self.sub_grid(min, max).to_grid()
+    }
+
+    /// Crop down to the cells matching `pred` (`None` if nothing matches)
+    pub(crate) fn crop_to<F>(&self, pred: F) -> Option<Grid<T>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let (min, max) = self.bounding_box(pred)?;
+        Some(self.crop(min, max))
+    }
+}
+
+#[cfg(test)]
+fn sample_grid() -> Grid<char> {
+    // abc
+    // def
+    Grid {
+        width: 3,
+        height: 2,
+        data: "abcdef".chars().collect(),
+    }
+}
+
+#[cfg(test)]
+fn as_string(grid: &Grid<char>) -> String {
+    grid.rows()
+        .iter()
+        .map(|r| r.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_rotations() {
+    let grid = sample_grid();
+    assert_eq!(as_string(&grid.rotated_cw()), "da\neb\nfc");
+    assert_eq!(as_string(&grid.rotated(180)), "fed\ncba");
+    assert_eq!(as_string(&grid.rotated_ccw()), "cf\nbe\nad");
+    assert_eq!(as_string(&grid.rotated(360)), "abc\ndef");
+    assert_eq!(
+        as_string(&grid.view().rotate_cw().rotate_cw().rotate_cw().to_grid()),
+        as_string(&grid.rotated_ccw())
+    );
+}
+
+#[test]
+fn test_transpose_and_flips() {
+    let grid = sample_grid();
+    assert_eq!(as_string(&grid.transposed()), "ad\nbe\ncf");
+    assert_eq!(as_string(&grid.flipped_horizontal()), "cba\nfed");
+    assert_eq!(as_string(&grid.flipped_vertical()), "def\nabc");
+    // transposing a rotated view
+    assert_eq!(
+        as_string(&grid.view().rotate_cw().transpose().to_grid()),
+        "def\nabc"
+    );
+}
+
+#[test]
+fn test_crop_and_sub_grid() {
+    let grid = sample_grid();
+    assert_eq!(
+        as_string(&grid.crop((1, 0).into(), (2, 1).into())),
+        "bc\nef"
+    );
+    let view = grid.view().rotate_cw().sub_grid((0, 1).into(), 2, 2);
+    assert_eq!(as_string(&view.to_grid()), "eb\nfc");
+    assert_eq!(view.grid_coord((0, 0).into()), Some((1, 1).into()));
+    assert_eq!(
+        as_string(&grid.crop_to(|c| "be".contains(*c)).unwrap()),
+        "b\ne"
+    );
+}
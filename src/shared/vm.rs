@@ -0,0 +1,327 @@
+/// A tiny cycle-accurate CPU for the "run this made-up assembly" puzzles.
+///
+/// A day brings its own instruction set by implementing `Instruction`: how many cycles each
+/// instruction takes and what it does to the registers once it's done. Anything that has to
+/// watch the machine as it runs (sampling a register, drawing a screen) is an `Observer`, which
+/// sees the registers during every cycle, before the instruction in flight has finished.
+///
+/// `Debugger` wraps a `Vm` to single step it, stop on breakpoints and keep a register history
+/// that can be dumped as CSV.
+use std::fmt::{self, Display, Write};
+
+/// Named registers, in the order they were declared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Registers {
+    names: Vec<&'static str>,
+    values: Vec<i64>,
+}
+
+impl Registers {
+    pub(crate) fn new(registers: &[(&'static str, i64)]) -> Self {
+        Self {
+            names: registers.iter().map(|(name, _)| *name).collect(),
+            values: registers.iter().map(|(_, val)| *val).collect(),
+        }
+    }
+
+    fn index(&self, name: &str) -> usize {
+        self.names
+            .iter()
+            .position(|n| *n == name)
+            .unwrap_or_else(|| panic!("no register called {name}"))
+    }
+
+    pub(crate) fn get(&self, name: &str) -> i64 {
+        self.values[self.index(name)]
+    }
+
+    pub(crate) fn set(&mut self, name: &str, val: i64) {
+        let i = self.index(name);
+        self.values[i] = val;
+    }
+
+    pub(crate) fn names(&self) -> &[&'static str] {
+        &self.names
+    }
+
+    pub(crate) fn values(&self) -> &[i64] {
+        &self.values
+    }
+}
+
+pub(crate) trait Instruction {
+    /// Cycles it takes before the result shows up in the registers
+    fn cycles(&self) -> u32;
+    /// Applied at the end of the instruction's last cycle
+    fn execute(&self, registers: &mut Registers);
+}
+
+pub(crate) trait Observer {
+    /// Called during every cycle (numbered from 1), before the instruction running in it
+    /// completes
+    fn on_cycle(&mut self, cycle: u64, registers: &Registers);
+}
+
+impl<F: FnMut(u64, &Registers)> Observer for F {
+    fn on_cycle(&mut self, cycle: u64, registers: &Registers) {
+        self(cycle, registers)
+    }
+}
+
+pub(crate) struct Vm<P: Iterator> {
+    pub registers: Registers,
+    /// Cycles completed so far
+    pub cycle: u64,
+    program: P,
+    /// Instruction in flight and how many of its cycles are left
+    current: Option<(P::Item, u32)>,
+}
+
+impl<I: Instruction, P: Iterator<Item = I>> Vm<P> {
+    pub(crate) fn new(registers: Registers, program: impl IntoIterator<IntoIter = P>) -> Self {
+        Self {
+            registers,
+            cycle: 0,
+            program: program.into_iter(),
+            current: None,
+        }
+    }
+
+    /// Run a single cycle. Returns false once the program has run out.
+    pub(crate) fn step(&mut self, observers: &mut [&mut dyn Observer]) -> bool {
+        if self.current.is_none() {
+            self.current = self.program.next().map(|inst| {
+                let cycles = inst.cycles();
+                (inst, cycles)
+            });
+        }
+        let Some((inst, left)) = self.current.as_mut() else {
+            return false;
+        };
+        self.cycle += 1;
+        for observer in observers.iter_mut() {
+            observer.on_cycle(self.cycle, &self.registers);
+        }
+        *left = left.saturating_sub(1);
+        if *left == 0 {
+            inst.execute(&mut self.registers);
+            self.current = None;
+        }
+        true
+    }
+
+    /// Run the whole program
+    pub(crate) fn run(&mut self, observers: &mut [&mut dyn Observer]) {
+        while self.step(observers) {}
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Cmp {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+}
+
+/// Checked before each cycle runs, against the registers as that cycle will see them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Breakpoint {
+    /// about to run this cycle
+    Cycle(u64),
+    Register(&'static str, Cmp, i64),
+}
+
+impl Breakpoint {
+    fn hit(&self, next_cycle: u64, registers: &Registers) -> bool {
+        match *self {
+            Self::Cycle(cycle) => next_cycle == cycle,
+            Self::Register(name, cmp, val) => {
+                let reg = registers.get(name);
+                match cmp {
+                    Cmp::Eq => reg == val,
+                    Cmp::Ne => reg != val,
+                    Cmp::Lt => reg < val,
+                    Cmp::Gt => reg > val,
+                }
+            }
+        }
+    }
+}
+
+impl Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle(cycle) => write!(f, "cycle {cycle}"),
+            Self::Register(name, cmp, val) => {
+                let cmp = match cmp {
+                    Cmp::Eq => "==",
+                    Cmp::Ne => "!=",
+                    Cmp::Lt => "<",
+                    Cmp::Gt => ">",
+                };
+                write!(f, "{name} {cmp} {val}")
+            }
+        }
+    }
+}
+
+/// Keeps the history, then passes the cycle on to the caller's observers
+struct Recording<'h, 'o, 'p> {
+    history: &'h mut Vec<(u64, Vec<i64>)>,
+    observers: &'o mut [&'p mut dyn Observer],
+}
+
+impl Observer for Recording<'_, '_, '_> {
+    fn on_cycle(&mut self, cycle: u64, registers: &Registers) {
+        self.history.push((cycle, registers.values().to_vec()));
+        for observer in self.observers.iter_mut() {
+            observer.on_cycle(cycle, registers);
+        }
+    }
+}
+
+pub(crate) struct Debugger<P: Iterator> {
+    pub vm: Vm<P>,
+    pub breakpoints: Vec<Breakpoint>,
+    /// Registers during each cycle that has run
+    history: Vec<(u64, Vec<i64>)>,
+    /// stopped at a breakpoint, and hasn't run a cycle since
+    stopped: bool,
+}
+
+impl<I: Instruction, P: Iterator<Item = I>> Debugger<P> {
+    pub(crate) fn new(vm: Vm<P>) -> Self {
+        Self {
+            vm,
+            breakpoints: vec![],
+            history: vec![],
+            stopped: false,
+        }
+    }
+
+    /// Run one cycle, ignoring breakpoints
+    pub(crate) fn step(&mut self, observers: &mut [&mut dyn Observer]) -> bool {
+        self.stopped = false;
+        let mut recording = Recording {
+            history: &mut self.history,
+            observers,
+        };
+        self.vm.step(&mut [&mut recording])
+    }
+
+    /// Run until the next breakpoint (which is returned), or the end of the program. Resuming
+    /// from a breakpoint runs at least one cycle first, so it doesn't stop on the same one again.
+    pub(crate) fn resume(&mut self, observers: &mut [&mut dyn Observer]) -> Option<Breakpoint> {
+        if self.stopped && !self.step(observers) {
+            return None;
+        }
+        loop {
+            let next = self.vm.cycle + 1;
+            let registers = &self.vm.registers;
+            if let Some(i) = self.breakpoints.iter().position(|b| b.hit(next, registers)) {
+                self.stopped = true;
+                return Some(self.breakpoints[i].clone());
+            }
+            if !self.step(observers) {
+                return None;
+            }
+        }
+    }
+
+    /// `cycle,<register>...` header, then a line for every cycle that has run
+    pub(crate) fn history_csv(&self) -> String {
+        let mut csv = format!("cycle,{}\n", self.vm.registers.names().join(","));
+        for (cycle, values) in self.history.iter() {
+            let values = values.iter().map(i64::to_string).collect::<Vec<_>>();
+            writeln!(csv, "{cycle},{}", values.join(",")).unwrap();
+        }
+        csv
+    }
+}
+
+/// Test instruction set: `Add(reg, val)` takes 2 cycles, `Wait(n)` takes n
+#[cfg(test)]
+enum Op {
+    Add(&'static str, i64),
+    Wait(u32),
+}
+
+#[cfg(test)]
+impl Instruction for Op {
+    fn cycles(&self) -> u32 {
+        match self {
+            Op::Add(..) => 2,
+            Op::Wait(n) => *n,
+        }
+    }
+
+    fn execute(&self, registers: &mut Registers) {
+        if let Op::Add(reg, val) = self {
+            registers.set(reg, registers.get(reg) + val);
+        }
+    }
+}
+
+#[test]
+fn test_vm_cycles_and_observers() {
+    let program = vec![
+        Op::Add("a", 5),
+        Op::Wait(3),
+        Op::Add("b", -1),
+        Op::Add("a", 1),
+    ];
+    let mut vm = Vm::new(Registers::new(&[("a", 1), ("b", 0)]), program);
+    let mut seen = vec![];
+    let mut watch = |cycle: u64, regs: &Registers| seen.push((cycle, regs.get("a"), regs.get("b")));
+    vm.run(&mut [&mut watch]);
+
+    assert_eq!(vm.cycle, 9);
+    assert_eq!(vm.registers.values(), &[7, -1]);
+    // the add only lands after its second cycle
+    assert_eq!(&seen[..3], &[(1, 1, 0), (2, 1, 0), (3, 6, 0)]);
+    assert_eq!(seen[8], (9, 6, -1));
+}
+
+#[test]
+fn test_debugger() {
+    let program = vec![Op::Add("x", 2), Op::Wait(1), Op::Add("x", 3), Op::Wait(5)];
+    let vm = Vm::new(Registers::new(&[("x", 0)]), program);
+    let mut debugger = Debugger::new(vm);
+    debugger.breakpoints = vec![Breakpoint::Register("x", Cmp::Gt, 4), Breakpoint::Cycle(8)];
+
+    let hit = debugger.resume(&mut []);
+    assert_eq!(hit, Some(Breakpoint::Register("x", Cmp::Gt, 4)));
+    assert_eq!((debugger.vm.cycle, debugger.vm.registers.get("x")), (5, 5));
+    // x stays above 4, but resuming always makes progress first
+    let hit = debugger.resume(&mut []);
+    assert_eq!(hit.map(|b| b.to_string()), Some("x > 4".to_string()));
+    debugger.breakpoints.remove(0);
+    assert_eq!(debugger.resume(&mut []), Some(Breakpoint::Cycle(8)));
+    assert_eq!(debugger.vm.cycle, 7);
+    assert_eq!(debugger.resume(&mut []), None);
+
+    let csv = debugger.history_csv();
+    assert!(csv.starts_with("cycle,x\n1,0\n2,0\n3,2\n4,2\n5,2\n6,5\n"));
+    assert_eq!(csv.lines().count(), 11);
+}
+
+#[test]
+fn test_breakpoints_before_the_first_cycle() {
+    let program = || vec![Op::Wait(2), Op::Add("x", 1)];
+    let mut debugger = Debugger::new(Vm::new(Registers::new(&[("x", 0)]), program()));
+    debugger.breakpoints = vec![Breakpoint::Cycle(1), Breakpoint::Cycle(2)];
+    assert_eq!(debugger.resume(&mut []), Some(Breakpoint::Cycle(1)));
+    assert_eq!(debugger.vm.cycle, 0);
+    assert_eq!(debugger.resume(&mut []), Some(Breakpoint::Cycle(2)));
+    assert_eq!(debugger.vm.cycle, 1);
+
+    // already true before anything runs
+    let mut debugger = Debugger::new(Vm::new(Registers::new(&[("x", 0)]), program()));
+    debugger.breakpoints = vec![Breakpoint::Register("x", Cmp::Eq, 0)];
+    assert_eq!(
+        debugger.resume(&mut []),
+        Some(Breakpoint::Register("x", Cmp::Eq, 0))
+    );
+    assert_eq!(debugger.vm.cycle, 0);
+}
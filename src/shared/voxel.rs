@@ -0,0 +1,384 @@
+/// 3D integer vectors and a dense, bounds-aware voxel grid.
+///
+/// `Grid3` stores one value per cell of an inclusive `Bounds3` box, so lookups are just index
+/// math instead of hashing. Coordinates are signed and the box doesn't have to start at the origin,
+/// which makes it easy to pad a shape with a layer of empty cells before flood filling around it.
+use std::{
+    collections::HashSet,
+    ops::{Add, Sub},
+};
+
+use super::flood::{self, Connectivity, Fillable, Region};
+
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Vec3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+/// The 6 face-adjacent directions
+pub(crate) const FACES: [(isize, isize, isize); 6] = [
+    (-1, 0, 0), // left
+    (0, 1, 0),  // up
+    (1, 0, 0),  // right
+    (0, -1, 0), // down
+    (0, 0, 1),  // above
+    (0, 0, -1), // below
+];
+
+impl std::fmt::Debug for Vec3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({:>2}, {:>2}, {:>2})", self.x, self.y, self.z)
+    }
+}
+
+impl From<(isize, isize, isize)> for Vec3 {
+    fn from(value: (isize, isize, isize)) -> Self {
+        Self {
+            x: value.0,
+            y: value.1,
+            z: value.2,
+        }
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl Add<(isize, isize, isize)> for Vec3 {
+    type Output = Vec3;
+    fn add(self, rhs: (isize, isize, isize)) -> Self::Output {
+        self + Vec3::from(rhs)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl Vec3 {
+    pub(crate) fn new(x: isize, y: isize, z: isize) -> Self {
+        Self { x, y, z }
+    }
+
+    pub(crate) fn manhattan_dist(&self, other: &Vec3) -> isize {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    /// Component-wise minimum
+    pub(crate) fn min(self, other: Vec3) -> Vec3 {
+        (
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+            .into()
+    }
+
+    /// Component-wise maximum
+    pub(crate) fn max(self, other: Vec3) -> Vec3 {
+        (
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+            .into()
+    }
+
+    /// Face-adjacent neighbors
+    pub(crate) fn neighbors6(self) -> [Vec3; 6] {
+        FACES.map(|d| self + d)
+    }
+
+    /// Face, edge and corner-adjacent neighbors
+    pub(crate) fn neighbors26(self) -> Vec<Vec3> {
+        let mut neighbors = Vec::with_capacity(26);
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if (dx, dy, dz) != (0, 0, 0) {
+                        neighbors.push(self + (dx, dy, dz));
+                    }
+                }
+            }
+        }
+        neighbors
+    }
+
+    pub(crate) fn neighbors(self, connectivity: Connectivity) -> Vec<Vec3> {
+        match connectivity {
+            Connectivity::Orthogonal => self.neighbors6().to_vec(),
+            Connectivity::All => self.neighbors26(),
+        }
+    }
+}
+
+/// Inclusive axis-aligned box
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Bounds3 {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Bounds3 {
+    pub(crate) fn new(min: Vec3, max: Vec3) -> Self {
+        assert!(min.x <= max.x && min.y <= max.y && min.z <= max.z);
+        Self { min, max }
+    }
+
+    /// Smallest box containing all the points, `None` if there aren't any
+    pub(crate) fn from_points<'a, I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a Vec3>,
+    {
+        points.into_iter().fold(None, |bounds, &pt| match bounds {
+            None => Some(Self { min: pt, max: pt }),
+            Some(b) => Some(Self {
+                min: b.min.min(pt),
+                max: b.max.max(pt),
+            }),
+        })
+    }
+
+    /// Grow the box by `n` cells in every direction
+    pub(crate) fn expand(self, n: isize) -> Self {
+        Self::new(self.min + (-n, -n, -n), self.max + (n, n, n))
+    }
+
+    pub(crate) fn contains(&self, pt: Vec3) -> bool {
+        (self.min.x..=self.max.x).contains(&pt.x)
+            && (self.min.y..=self.max.y).contains(&pt.y)
+            && (self.min.z..=self.max.z).contains(&pt.z)
+    }
+
+    /// Size of the box along each axis, in cells
+    pub(crate) fn dims(&self) -> (usize, usize, usize) {
+        let size = self.max - self.min;
+        (
+            size.x as usize + 1,
+            size.y as usize + 1,
+            size.z as usize + 1,
+        )
+    }
+
+    pub(crate) fn volume(&self) -> usize {
+        let (w, h, d) = self.dims();
+        w * h * d
+    }
+
+    /// Is the point on the outermost layer of the box?
+    pub(crate) fn on_boundary(&self, pt: Vec3) -> bool {
+        self.contains(pt)
+            && (pt.x == self.min.x
+                || pt.x == self.max.x
+                || pt.y == self.min.y
+                || pt.y == self.max.y
+                || pt.z == self.min.z
+                || pt.z == self.max.z)
+    }
+
+    /// Every point in the box, x fastest then y then z
+    pub(crate) fn points(&self) -> impl Iterator<Item = Vec3> {
+        let Self { min, max } = *self;
+        (min.z..=max.z).flat_map(move |z| {
+            (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Vec3::new(x, y, z)))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Grid3<T> {
+    bounds: Bounds3,
+    data: Vec<T>,
+}
+
+impl<T: Clone> Grid3<T> {
+    pub(crate) fn new(bounds: Bounds3, fill: T) -> Self {
+        Self {
+            bounds,
+            data: vec![fill; bounds.volume()],
+        }
+    }
+
+    /// Grid just big enough for `points` plus `padding` cells all around. The points are set to
+    /// `filled`, everything else to `empty`.
+    pub(crate) fn from_points<'a, I>(points: I, filled: T, empty: T, padding: isize) -> Self
+    where
+        I: IntoIterator<Item = &'a Vec3> + Clone,
+    {
+        let bounds = Bounds3::from_points(points.clone())
+            .unwrap_or(Bounds3::new(Vec3::default(), Vec3::default()))
+            .expand(padding);
+        let mut grid = Self::new(bounds, empty);
+        for &pt in points {
+            grid.set(pt, filled.clone());
+        }
+        grid
+    }
+}
+
+impl<T> Grid3<T> {
+    pub(crate) fn bounds(&self) -> Bounds3 {
+        self.bounds
+    }
+
+    fn index(&self, pt: Vec3) -> Option<usize> {
+        if !self.bounds.contains(pt) {
+            return None;
+        }
+        let (w, h, _) = self.bounds.dims();
+        let rel = pt - self.bounds.min;
+        Some((rel.z as usize * h + rel.y as usize) * w + rel.x as usize)
+    }
+
+    pub(crate) fn in_bounds(&self, pt: Vec3) -> bool {
+        self.bounds.contains(pt)
+    }
+
+    pub(crate) fn get(&self, pt: Vec3) -> Option<&T> {
+        self.index(pt).map(|i| &self.data[i])
+    }
+
+    pub(crate) fn get_mut(&mut self, pt: Vec3) -> Option<&mut T> {
+        self.index(pt).map(|i| &mut self.data[i])
+    }
+
+    /// Panics if `pt` is out of bounds
+    pub(crate) fn set(&mut self, pt: Vec3, val: T) {
+        let i = self
+            .index(pt)
+            .unwrap_or_else(|| panic!("{pt:?} out of bounds {:?}", self.bounds));
+        self.data[i] = val;
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Vec3, &T)> {
+        self.bounds.points().zip(self.data.iter())
+    }
+
+    /// In-bounds neighbors of `pt`
+    pub(crate) fn neighbors(
+        &self,
+        pt: Vec3,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = Vec3> + '_ {
+        pt.neighbors(connectivity)
+            .into_iter()
+            .filter(|n| self.in_bounds(*n))
+    }
+
+    /// Every cell reachable from `seeds` through cells where `passable` is true.
+    /// Seeds that aren't passable (or out of bounds) are ignored.
+    pub(crate) fn flood_fill<F>(
+        &self,
+        seeds: impl IntoIterator<Item = Vec3>,
+        connectivity: Connectivity,
+        passable: F,
+    ) -> HashSet<Vec3>
+    where
+        F: Fn(&T) -> bool,
+    {
+        flood::flood_fill(self, seeds, connectivity, passable)
+    }
+
+    /// Split all the cells where `include` is true into connected groups
+    pub(crate) fn components<F>(&self, connectivity: Connectivity, include: F) -> Vec<Region<Vec3>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        flood::label_regions(self, connectivity, include).regions
+    }
+
+    /// Number of faces between a `solid` cell and a non-solid (or out of bounds) one
+    pub(crate) fn surface_area<F>(&self, solid: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.iter()
+            .filter(|(_, v)| solid(v))
+            .flat_map(|(pt, _)| pt.neighbors6())
+            .filter(|n| !self.get(*n).is_some_and(&solid))
+            .count()
+    }
+
+    /// Like `surface_area`, but only counting faces that can be reached from outside the grid.
+    /// Air pockets sealed inside the shape don't count.
+    pub(crate) fn exterior_surface_area<F>(&self, solid: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        let outside = self.flood_fill(
+            self.bounds
+                .points()
+                .filter(|pt| self.bounds.on_boundary(*pt)),
+            Connectivity::Orthogonal,
+            |v| !solid(v),
+        );
+        self.iter()
+            .filter(|(_, v)| solid(v))
+            .flat_map(|(pt, _)| pt.neighbors6())
+            .filter(|n| !self.in_bounds(*n) || outside.contains(n))
+            .count()
+    }
+}
+
+impl<T> Fillable for Grid3<T> {
+    type Pos = Vec3;
+    type Cell = T;
+
+    fn get_cell(&self, pos: Vec3) -> Option<&T> {
+        self.get(pos)
+    }
+
+    fn adjacent(&self, pos: Vec3, connectivity: Connectivity) -> Vec<Vec3> {
+        self.neighbors(pos, connectivity).collect()
+    }
+
+    fn positions(&self) -> Vec<Vec3> {
+        self.bounds.points().collect()
+    }
+}
+
+#[test]
+fn test_neighbors_and_bounds() {
+    let origin = Vec3::default();
+    assert_eq!(origin.neighbors26().len(), 26);
+    assert!(origin
+        .neighbors6()
+        .iter()
+        .all(|n| n.manhattan_dist(&origin) == 1));
+    let bounds = Bounds3::from_points(&[Vec3::new(1, -2, 3), Vec3::new(-1, 2, 0)]).unwrap();
+    assert_eq!(bounds.min, Vec3::new(-1, -2, 0));
+    assert_eq!(bounds.dims(), (3, 5, 4));
+    assert_eq!(bounds.points().count(), bounds.volume());
+}
+
+#[test]
+fn test_hollow_cube() {
+    // 3x3x3 shell with a single air pocket in the middle
+    let shell: Vec<Vec3> = Bounds3::new(Vec3::default(), Vec3::new(2, 2, 2))
+        .points()
+        .filter(|pt| *pt != Vec3::new(1, 1, 1))
+        .collect();
+    let grid = Grid3::from_points(&shell, true, false, 1);
+    assert_eq!(grid.surface_area(|v| *v), 54 + 6);
+    assert_eq!(grid.exterior_surface_area(|v| *v), 54);
+    let pockets = grid.components(Connectivity::Orthogonal, |v| !*v);
+    assert_eq!(pockets.len(), 2);
+    assert_eq!(pockets[1].cells, vec![Vec3::new(1, 1, 1)]);
+}